@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Split arbitrary input into two buffers and assert compare_hashes over the
+// parsed fingerprints always yields a clamped 0-100 score without crashing.
+fuzz_target!(|data: &[u8]| {
+    lavinhash::fuzz::compare_split(data);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Feed random/truncated buffers into FuzzyFingerprint::from_bytes and assert it
+// returns Err or a stable fingerprint rather than crashing.
+fuzz_target!(|data: &[u8]| {
+    lavinhash::fuzz::parse(data);
+});
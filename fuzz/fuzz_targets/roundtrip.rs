@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Assert generate_hash -> to_bytes -> from_bytes is stable for arbitrary input.
+fuzz_target!(|data: &[u8]| {
+    lavinhash::fuzz::roundtrip(data);
+});
@@ -3,6 +3,8 @@
 //! Fixed-size 8192-bit Bloom filter optimized for L1/L2 cache.
 //! Uses 5 hash functions with adaptive modulus to prevent saturation.
 
+use smallvec::SmallVec;
+
 /// Bloom filter size in bits (8192 bits = 1024 bytes = 1KB)
 /// Optimal size with adaptive modulus scaling
 pub const BLOOM_SIZE_BITS: usize = 8_192;
@@ -23,29 +25,172 @@ const HASH_SEEDS: [u64; NUM_HASH_FUNCTIONS] = [
     0xb492b66fbe98f273, // seed 5
 ];
 
-/// Fixed-size Bloom Filter (Heap Allocated)
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Pluggable index strategy for a [`BloomFilter`]
+///
+/// Mirrors the filter-policy extension point found in LSM-tree libraries: the
+/// default [`FxBloomHasher`] uses the fast FxHash routine, but callers can swap
+/// in a cryptographically stronger hash for adversarial inputs or a
+/// tokenizer-aware hasher without forking the filter. The `name` is recorded in
+/// a fingerprint so filters built with different policies refuse comparison.
+pub trait BloomHasher: Send + Sync {
+    /// Compute the `k` bit indices for `data` over `num_bits` slots
+    fn indices(&self, data: &[u8], num_bits: usize, k: usize) -> SmallVec<[usize; 8]>;
+
+    /// Stable identifier for this policy, stored alongside a fingerprint
+    fn name(&self) -> &'static str;
+
+    /// Clone into a fresh boxed trait object (enables `BloomFilter: Clone`)
+    fn clone_box(&self) -> Box<dyn BloomHasher>;
+}
+
+/// Default FxHash-based hasher using Kirsch–Mitzenmacher double hashing
+#[derive(Clone, Debug, Default)]
+pub struct FxBloomHasher;
+
+impl BloomHasher for FxBloomHasher {
+    #[inline]
+    fn indices(&self, data: &[u8], num_bits: usize, k: usize) -> SmallVec<[usize; 8]> {
+        let h1 = BloomFilter::fx_hash(data, HASH_SEEDS[0]);
+        let h2 = BloomFilter::fx_hash(data, HASH_SEEDS[1]) | 1;
+
+        let mut indices = SmallVec::with_capacity(k);
+        for i in 0..k {
+            let combined = h1.wrapping_add((i as u64).wrapping_mul(h2));
+            indices.push((combined as usize) % num_bits);
+        }
+        indices
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "fx"
+    }
+
+    #[inline]
+    fn clone_box(&self) -> Box<dyn BloomHasher> {
+        Box::new(FxBloomHasher)
+    }
+}
+
+/// Seed for the MurmurHash3-backed policy
+const MURMUR_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// MurmurHash3-based hasher using Kirsch–Mitzenmacher double hashing
+///
+/// Derives all `k` indices from a single [`murmur3_x64_128`] call: the two
+/// 64-bit halves of the digest become `h1` and `h2`, then `g_i = h1 + i·h2`.
+/// Compared to [`FxBloomHasher`] this is one fast non-cryptographic hash per
+/// feature with less collision clustering, and the little-endian digest gives
+/// architecture-stable bit positions for serialized filters.
+///
+/// [`murmur3_x64_128`]: super::murmur::murmur3_x64_128
+#[derive(Clone, Debug, Default)]
+pub struct MurmurBloomHasher;
+
+impl BloomHasher for MurmurBloomHasher {
+    #[inline]
+    fn indices(&self, data: &[u8], num_bits: usize, k: usize) -> SmallVec<[usize; 8]> {
+        let (h1, h2) = super::murmur::murmur3_x64_128(data, MURMUR_SEED);
+        // Keep h2 odd so successive probes are distinct modulo any power of two.
+        let h2 = h2 | 1;
+
+        let mut indices = SmallVec::with_capacity(k);
+        for i in 0..k {
+            let combined = h1.wrapping_add((i as u64).wrapping_mul(h2));
+            indices.push((combined as usize) % num_bits);
+        }
+        indices
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "murmur3"
+    }
+
+    #[inline]
+    fn clone_box(&self) -> Box<dyn BloomHasher> {
+        Box::new(MurmurBloomHasher)
+    }
+}
+
+/// Bloom Filter (Heap Allocated)
+///
+/// Defaults to the fixed 8192-bit / 5-hash layout, but [`with_capacity`]
+/// sizes `m` (bit count) and `k` (hash count) to an expected feature count and
+/// target false-positive rate. The chosen `m`/`k` are stored so `get_indices`,
+/// serialization, and similarity all respect the filter's actual size.
+///
+/// [`with_capacity`]: BloomFilter::with_capacity
 pub struct BloomFilter {
     // Use Vec for heap allocation. We enforce size logic in methods.
-    bits: Vec<u64>, 
+    bits: Vec<u64>,
+    // Number of addressable bits (`m`); always `bits.len() * 64`.
+    num_bits: usize,
+    // Number of hash functions (`k`).
+    num_hashes: usize,
+    // Pluggable index strategy (default [`FxBloomHasher`]).
+    hasher: Box<dyn BloomHasher>,
 }
 
 impl BloomFilter {
-    /// Create a new empty Bloom filter
+    /// Create a new empty Bloom filter with the default 8192-bit / 5-hash layout
     #[inline]
     pub fn new() -> Self {
-        Self { 
-            bits: vec![0u64; BLOOM_WORDS]
+        Self {
+            bits: vec![0u64; BLOOM_WORDS],
+            num_bits: BLOOM_SIZE_BITS,
+            num_hashes: NUM_HASH_FUNCTIONS,
+            hasher: Box::new(FxBloomHasher),
+        }
+    }
+
+    /// Create an empty filter that uses a custom [`BloomHasher`] policy
+    #[inline]
+    pub fn with_hasher(hasher: Box<dyn BloomHasher>) -> Self {
+        Self {
+            bits: vec![0u64; BLOOM_WORDS],
+            num_bits: BLOOM_SIZE_BITS,
+            num_hashes: NUM_HASH_FUNCTIONS,
+            hasher,
         }
     }
 
-    /// Create from raw bytes (for deserialization)
+    /// Name of the hashing policy backing this filter
+    #[inline]
+    pub fn hasher_name(&self) -> &'static str {
+        self.hasher.name()
+    }
+
+    /// Create an empty filter sized for `expected_features` at `target_fpr`
+    ///
+    /// Computes the optimal bit count `m = ceil(-(n * ln p) / (ln 2)^2)` and
+    /// hash-function count `k = round((m / n) * ln 2)`, rounding `m` up to a
+    /// multiple of 64 so it maps onto whole `u64` words.
+    pub fn with_capacity(expected_features: usize, target_fpr: f64) -> Self {
+        let num_bits = optimal_num_bits(expected_features, target_fpr);
+        let num_hashes = optimal_num_hashes(num_bits, expected_features);
+        Self {
+            bits: vec![0u64; num_bits / 64],
+            num_bits,
+            num_hashes,
+            hasher: Box::new(FxBloomHasher),
+        }
+    }
+
+    /// Create from raw bytes (for deserialization), using the default hash count
+    ///
+    /// The bit count is inferred from the buffer length, which must be a
+    /// non-zero multiple of 8 bytes.
     #[inline]
     pub fn from_bytes(bytes: &[u8]) -> Self {
-        assert_eq!(bytes.len(), BLOOM_SIZE_BYTES, "BloomFilter bytes must be exactly {} bytes", BLOOM_SIZE_BYTES);
+        assert!(
+            !bytes.is_empty() && bytes.len() % 8 == 0,
+            "BloomFilter bytes must be a non-zero multiple of 8, got {}",
+            bytes.len()
+        );
+
+        let mut bits_vec = Vec::with_capacity(bytes.len() / 8);
 
-        let mut bits_vec = Vec::with_capacity(BLOOM_WORDS);
-        
         for chunk in bytes.chunks_exact(8) {
             bits_vec.push(u64::from_le_bytes([
                 chunk[0], chunk[1], chunk[2], chunk[3],
@@ -53,8 +198,11 @@ impl BloomFilter {
             ]));
         }
 
-        Self { 
-             bits: bits_vec
+        Self {
+            num_bits: bits_vec.len() * 64,
+            num_hashes: NUM_HASH_FUNCTIONS,
+            bits: bits_vec,
+            hasher: Box::new(FxBloomHasher),
         }
     }
 
@@ -83,16 +231,25 @@ impl BloomFilter {
     }
 
     /// Get bit indices for a given data
+    ///
+    /// Delegates to the filter's [`BloomHasher`] policy, which by default
+    /// ([`FxBloomHasher`]) uses the Kirsch–Mitzenmacher double-hashing
+    /// construction over `self.num_bits` slots.
     #[inline]
-    fn get_indices(&self, data: &[u8]) -> [usize; NUM_HASH_FUNCTIONS] {
-        let mut indices = [0usize; NUM_HASH_FUNCTIONS];
+    fn get_indices(&self, data: &[u8]) -> SmallVec<[usize; 8]> {
+        self.hasher.indices(data, self.num_bits, self.num_hashes)
+    }
 
-        for i in 0..NUM_HASH_FUNCTIONS {
-            let hash = Self::fx_hash(data, HASH_SEEDS[i]);
-            indices[i] = (hash as usize) % BLOOM_SIZE_BITS;
-        }
+    /// Number of addressable bits (`m`) in this filter
+    #[inline]
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
 
-        indices
+    /// Number of hash functions (`k`) this filter uses
+    #[inline]
+    pub fn num_hashes(&self) -> usize {
+        self.num_hashes
     }
 
     /// Set a bit at the given index
@@ -133,16 +290,7 @@ impl BloomFilter {
     /// Uses fast bitwise operations
     #[inline]
     pub fn jaccard_similarity(&self, other: &BloomFilter) -> f32 {
-        let mut intersection = 0u32;
-        let mut union = 0u32;
-
-        for i in 0..BLOOM_WORDS {
-            let and_bits = self.bits[i] & other.bits[i];
-            let or_bits = self.bits[i] | other.bits[i];
-
-            intersection += and_bits.count_ones();
-            union += or_bits.count_ones();
-        }
+        let (intersection, union) = and_or_popcount(&self.bits, &other.bits);
 
         if union == 0 {
             // Both filters are empty - they are identical
@@ -152,11 +300,79 @@ impl BloomFilter {
         intersection as f32 / union as f32
     }
 
+    /// Jaccard similarity that refuses to compare differently-sized filters
+    ///
+    /// Word-wise comparison only makes sense when both filters share the same
+    /// `m`/`k`; otherwise the bits address different hash spaces. Returns
+    /// [`BloomError::SizeMismatch`] rather than silently misaligning words.
+    #[inline]
+    pub fn try_jaccard_similarity(&self, other: &BloomFilter) -> Result<f32, BloomError> {
+        if self.num_bits != other.num_bits || self.num_hashes != other.num_hashes {
+            return Err(BloomError::SizeMismatch);
+        }
+        Ok(self.jaccard_similarity(other))
+    }
+
+    /// Estimate the number of distinct elements inserted (Swamidass–Baldi)
+    ///
+    /// `n* = -(m / k) · ln(1 − X/m)` where `X` is the set-bit count, `m` the bit
+    /// count and `k` the hash count. This inverts the false-positive relation to
+    /// recover an element count, correcting the overestimation that raw bit
+    /// counts suffer as the filter fills. A fully saturated filter (`X == m`)
+    /// returns a finite clamp rather than infinity.
+    #[inline]
+    pub fn estimated_cardinality(&self) -> f64 {
+        let m = self.num_bits as f64;
+        let k = self.num_hashes as f64;
+        let x = self.count_set_bits() as f64;
+
+        if x >= m {
+            // Fully saturated: ln(0) would diverge, so clamp just below m.
+            return -(m / k) * (1.0 / m).ln();
+        }
+
+        -(m / k) * (1.0 - x / m).ln()
+    }
+
+    /// Cardinality-corrected Jaccard similarity with another filter
+    ///
+    /// Derives corrected cardinalities for `A`, `B`, and `A ∪ B` (from the
+    /// OR-ed bit count) and returns `(|A*| + |B*| − |A∪B*|) / |A∪B*|`, which is
+    /// far more accurate than raw bit Jaccard for near-saturated fingerprints.
+    #[inline]
+    pub fn estimated_jaccard(&self, other: &BloomFilter) -> f64 {
+        let card_a = self.estimated_cardinality();
+        let card_b = other.estimated_cardinality();
+
+        // Cardinality of the union from the OR-ed bit count.
+        let m = self.num_bits as f64;
+        let k = self.num_hashes as f64;
+        let union_bits: u32 = self
+            .bits
+            .iter()
+            .zip(other.bits.iter())
+            .map(|(a, b)| (a | b).count_ones())
+            .sum();
+        let x = union_bits as f64;
+        let card_union = if x >= m {
+            -(m / k) * (1.0 / m).ln()
+        } else {
+            -(m / k) * (1.0 - x / m).ln()
+        };
+
+        if card_union <= 0.0 {
+            // Both filters empty -> identical by convention.
+            return 1.0;
+        }
+
+        ((card_a + card_b - card_union) / card_union).clamp(0.0, 1.0)
+    }
+
     /// Merge another Bloom filter into this one (bitwise OR)
     #[inline]
     pub fn merge(&mut self, other: &BloomFilter) {
-        for i in 0..BLOOM_WORDS {
-            self.bits[i] |= other.bits[i];
+        for (a, b) in self.bits.iter_mut().zip(other.bits.iter()) {
+            *a |= b;
         }
     }
 
@@ -177,6 +393,35 @@ impl BloomFilter {
     pub fn is_empty(&self) -> bool {
         self.bits.iter().all(|&word| word == 0)
     }
+
+    /// Test whether every set bit of `self` is also set in `other`
+    ///
+    /// Enables a "bloom of blooms" fast-rejection workflow: if a query filter
+    /// is not a subset of a coarse aggregate (built with [`merge`]), none of the
+    /// aggregate's members can match it and the exact comparison can be skipped
+    /// entirely. Differently-sized filters are never subsets.
+    ///
+    /// [`merge`]: BloomFilter::merge
+    #[inline]
+    pub fn is_subset_of(&self, other: &BloomFilter) -> bool {
+        if self.num_bits != other.num_bits {
+            return false;
+        }
+        self.bits
+            .iter()
+            .zip(other.bits.iter())
+            .all(|(a, b)| a & !b == 0)
+    }
+
+    /// Test a batch of features against this filter in a single pass
+    ///
+    /// Returns true only if every feature might be present (see [`contains`]).
+    ///
+    /// [`contains`]: BloomFilter::contains
+    #[inline]
+    pub fn might_contain_all(&self, features: &[&[u8]]) -> bool {
+        features.iter().all(|feature| self.contains(feature))
+    }
 }
 
 impl Default for BloomFilter {
@@ -185,6 +430,249 @@ impl Default for BloomFilter {
     }
 }
 
+impl Clone for BloomFilter {
+    fn clone(&self) -> Self {
+        Self {
+            bits: self.bits.clone(),
+            num_bits: self.num_bits,
+            num_hashes: self.num_hashes,
+            hasher: self.hasher.clone_box(),
+        }
+    }
+}
+
+impl std::fmt::Debug for BloomFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BloomFilter")
+            .field("num_bits", &self.num_bits)
+            .field("num_hashes", &self.num_hashes)
+            .field("hasher", &self.hasher.name())
+            .field("set_bits", &self.count_set_bits())
+            .finish()
+    }
+}
+
+impl PartialEq for BloomFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.num_bits == other.num_bits
+            && self.num_hashes == other.num_hashes
+            && self.hasher.name() == other.hasher.name()
+            && self.bits == other.bits
+    }
+}
+
+impl Eq for BloomFilter {}
+
+/// Errors produced by Bloom filter operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BloomError {
+    /// Two filters with mismatched `m`/`k` cannot be compared bit-for-bit
+    SizeMismatch,
+}
+
+impl std::fmt::Display for BloomError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SizeMismatch => write!(f, "Bloom filters have mismatched size"),
+        }
+    }
+}
+
+impl std::error::Error for BloomError {}
+
+/// Compute the optimal bit count `m` for `n` features at false-positive rate `p`
+///
+/// `m = ceil(-(n * ln p) / (ln 2)^2)`, rounded up to a multiple of 64 so the
+/// filter maps onto whole 64-bit words. Degenerate inputs fall back to the
+/// default 8192-bit layout.
+#[inline]
+pub fn optimal_num_bits(expected_features: usize, target_fpr: f64) -> usize {
+    if expected_features == 0 || !(target_fpr > 0.0 && target_fpr < 1.0) {
+        return BLOOM_SIZE_BITS;
+    }
+
+    let n = expected_features as f64;
+    let ln2_sq = std::f64::consts::LN_2 * std::f64::consts::LN_2;
+    let m = (-(n * target_fpr.ln()) / ln2_sq).ceil() as usize;
+
+    // Round up to a multiple of 64 bits (whole words), never below one word.
+    m.max(64).div_ceil(64) * 64
+}
+
+/// Compute the optimal hash-function count `k = round((m / n) * ln 2)`
+#[inline]
+pub fn optimal_num_hashes(num_bits: usize, expected_features: usize) -> usize {
+    if expected_features == 0 {
+        return NUM_HASH_FUNCTIONS;
+    }
+    let k = ((num_bits as f64 / expected_features as f64) * std::f64::consts::LN_2).round() as usize;
+    k.max(1)
+}
+
+/// Popcounts of the word-wise AND and OR of two bit vectors
+///
+/// The intersection/union popcount is the inner loop of every bloom Jaccard, so
+/// it is dispatched to an AVX2 path (vectorized `and`/`or` over 256-bit lanes
+/// followed by hardware popcount) when the CPU supports it, falling back to the
+/// scalar loop otherwise. Output is identical on every path. The SIMD path is
+/// gated off under Miri, which does not model the intrinsics.
+#[inline]
+fn and_or_popcount(a: &[u64], b: &[u64]) -> (u32, u32) {
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: guarded by the runtime AVX2 feature check above.
+            return unsafe { and_or_popcount_avx2(a, b) };
+        }
+    }
+    and_or_popcount_scalar(a, b)
+}
+
+/// Scalar AND/OR popcount, and the fallback for non-AVX2 targets
+#[inline]
+fn and_or_popcount_scalar(a: &[u64], b: &[u64]) -> (u32, u32) {
+    let mut intersection = 0u32;
+    let mut union = 0u32;
+    for (x, y) in a.iter().zip(b.iter()) {
+        intersection += (x & y).count_ones();
+        union += (x | y).count_ones();
+    }
+    (intersection, union)
+}
+
+/// AVX2 AND/OR popcount over 256-bit (4-word) lanes with a scalar tail
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+#[target_feature(enable = "avx2")]
+unsafe fn and_or_popcount_avx2(a: &[u64], b: &[u64]) -> (u32, u32) {
+    use std::arch::x86_64::*;
+
+    let n = a.len().min(b.len());
+    let lanes = n / 4;
+
+    let mut intersection = 0u32;
+    let mut union = 0u32;
+
+    for c in 0..lanes {
+        let pa = _mm256_loadu_si256(a.as_ptr().add(c * 4) as *const __m256i);
+        let pb = _mm256_loadu_si256(b.as_ptr().add(c * 4) as *const __m256i);
+        let and = _mm256_and_si256(pa, pb);
+        let or = _mm256_or_si256(pa, pb);
+
+        let mut and_words = [0u64; 4];
+        let mut or_words = [0u64; 4];
+        _mm256_storeu_si256(and_words.as_mut_ptr() as *mut __m256i, and);
+        _mm256_storeu_si256(or_words.as_mut_ptr() as *mut __m256i, or);
+
+        for i in 0..4 {
+            intersection += and_words[i].count_ones();
+            union += or_words[i].count_ones();
+        }
+    }
+
+    // Tail words that don't fill a full 4-wide lane.
+    for i in lanes * 4..n {
+        intersection += (a[i] & b[i]).count_ones();
+        union += (a[i] | b[i]).count_ones();
+    }
+
+    (intersection, union)
+}
+
+/// Counting Bloom Filter for frequency-weighted fingerprints
+///
+/// Replaces the 1-bit slots of [`BloomFilter`] with an array of small
+/// saturating counters, so callers can both `remove` features and track how
+/// many times a feature was inserted. The compact 1-bit [`BloomFilter`] is
+/// kept for serialization; this variant is used when multiplicity matters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CountingBloomFilter {
+    // One saturating u8 counter per bit slot of the equivalent BloomFilter.
+    counts: Vec<u8>,
+}
+
+impl CountingBloomFilter {
+    /// Create a new empty counting filter
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0u8; BLOOM_SIZE_BITS],
+        }
+    }
+
+    /// Get slot indices for a given feature
+    ///
+    /// Shares the default [`FxBloomHasher`] double-hashing routine so the
+    /// positions set by `insert`/`to_bloom` match the ones a plain
+    /// [`BloomFilter::contains`] later queries.
+    #[inline]
+    fn get_indices(&self, data: &[u8]) -> [usize; NUM_HASH_FUNCTIONS] {
+        let computed = FxBloomHasher.indices(data, BLOOM_SIZE_BITS, NUM_HASH_FUNCTIONS);
+        let mut indices = [0usize; NUM_HASH_FUNCTIONS];
+        indices.copy_from_slice(&computed[..NUM_HASH_FUNCTIONS]);
+        indices
+    }
+
+    /// Insert a feature, incrementing each slot (saturating at 255)
+    #[inline]
+    pub fn insert(&mut self, data: &[u8]) {
+        let indices = self.get_indices(data);
+        for &idx in &indices {
+            self.counts[idx] = self.counts[idx].saturating_add(1);
+        }
+    }
+
+    /// Remove a feature, decrementing each slot (saturating at 0)
+    #[inline]
+    pub fn remove(&mut self, data: &[u8]) {
+        let indices = self.get_indices(data);
+        for &idx in &indices {
+            self.counts[idx] = self.counts[idx].saturating_sub(1);
+        }
+    }
+
+    /// Frequency-weighted (weighted Jaccard) similarity with another filter
+    /// J_w(A,B) = Σ min(a_i, b_i) / Σ max(a_i, b_i)
+    ///
+    /// Repeated n-grams contribute proportionally to their multiplicity.
+    #[inline]
+    pub fn weighted_similarity(&self, other: &CountingBloomFilter) -> f32 {
+        let mut min_sum = 0u64;
+        let mut max_sum = 0u64;
+
+        for i in 0..BLOOM_SIZE_BITS {
+            let a = self.counts[i] as u64;
+            let b = other.counts[i] as u64;
+            min_sum += a.min(b);
+            max_sum += a.max(b);
+        }
+
+        if max_sum == 0 {
+            // Both filters are empty - they are identical
+            return 1.0;
+        }
+
+        min_sum as f32 / max_sum as f32
+    }
+
+    /// Collapse into a plain 1-bit [`BloomFilter`] for compact serialization
+    #[inline]
+    pub fn to_bloom(&self) -> BloomFilter {
+        let mut filter = BloomFilter::new();
+        for (idx, &count) in self.counts.iter().enumerate() {
+            if count > 0 {
+                filter.set_bit(idx);
+            }
+        }
+        filter
+    }
+}
+
+impl Default for CountingBloomFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Builder for creating Bloom filters from feature sets
 pub struct BloomFilterBuilder {
     filter: BloomFilter,
@@ -200,6 +688,15 @@ impl BloomFilterBuilder {
         }
     }
 
+    /// Create a builder whose filter is sized for `expected_features` at `target_fpr`
+    #[inline]
+    pub fn with_capacity(expected_features: usize, target_fpr: f64) -> Self {
+        Self {
+            filter: BloomFilter::with_capacity(expected_features, target_fpr),
+            feature_count: 0,
+        }
+    }
+
     #[inline]
     pub fn add_feature(&mut self, data: &[u8]) {
         self.filter.insert(data);
@@ -227,6 +724,43 @@ impl Default for BloomFilterBuilder {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_simd_popcount_matches_scalar() {
+        // Pseudo-random fingerprint pairs must yield identical intersection/union
+        // popcounts (and thus Jaccard scores) on the dispatched and scalar paths.
+        for seed in 0..8u64 {
+            let mut a = [0u64; 128];
+            let mut b = [0u64; 128];
+            let mut s = 0x1234_5678u64.wrapping_add(seed);
+            for i in 0..128 {
+                s = s.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                a[i] = s;
+                s = s.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                b[i] = s & a[i].rotate_left(17); // overlap some bits
+            }
+
+            assert_eq!(and_or_popcount(&a, &b), and_or_popcount_scalar(&a, &b));
+
+            let fa = BloomFilter::from_bytes(&words_to_bytes(&a));
+            let fb = BloomFilter::from_bytes(&words_to_bytes(&b));
+            assert_eq!(
+                fa.jaccard_similarity(&fb).to_bits(),
+                {
+                    let (i, u) = and_or_popcount_scalar(&a, &b);
+                    if u == 0 { 1.0f32 } else { i as f32 / u as f32 }.to_bits()
+                }
+            );
+        }
+    }
+
+    fn words_to_bytes(words: &[u64]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(words.len() * 8);
+        for w in words {
+            out.extend_from_slice(&w.to_le_bytes());
+        }
+        out
+    }
+
     #[test]
     fn test_bloom_insert_and_contains() {
         let mut bloom = BloomFilter::new();
@@ -369,6 +903,224 @@ mod tests {
         assert!(bloom.contains(b"Feature 3"));
     }
 
+    /// Minimal alternate policy used to exercise the pluggable hasher path.
+    #[derive(Clone)]
+    struct ConstHasher;
+
+    impl BloomHasher for ConstHasher {
+        fn indices(&self, _data: &[u8], num_bits: usize, k: usize) -> SmallVec<[usize; 8]> {
+            (0..k).map(|i| i % num_bits).collect()
+        }
+        fn name(&self) -> &'static str {
+            "const-test"
+        }
+        fn clone_box(&self) -> Box<dyn BloomHasher> {
+            Box::new(ConstHasher)
+        }
+    }
+
+    #[test]
+    fn test_estimated_cardinality() {
+        let mut bloom = BloomFilter::new();
+        for i in 0..500u32 {
+            bloom.insert(format!("item-{}", i).as_bytes());
+        }
+
+        // Estimator should land within ~15% of the true count of 500.
+        let est = bloom.estimated_cardinality();
+        assert!((est - 500.0).abs() / 500.0 < 0.15, "cardinality estimate off: {}", est);
+    }
+
+    #[test]
+    fn test_estimated_cardinality_saturated_is_finite() {
+        let mut bloom = BloomFilter::new();
+        for i in 0..1_000_000u32 {
+            bloom.insert(format!("x{}", i).as_bytes());
+        }
+        let est = bloom.estimated_cardinality();
+        assert!(est.is_finite(), "saturated estimate must be finite");
+    }
+
+    #[test]
+    fn test_estimated_jaccard_identical() {
+        let mut bloom = BloomFilter::new();
+        for i in 0..300u32 {
+            bloom.insert(format!("j{}", i).as_bytes());
+        }
+        let j = bloom.estimated_jaccard(&bloom);
+        assert!((j - 1.0).abs() < 0.05, "identical filters should estimate J~1.0, got {}", j);
+    }
+
+    #[test]
+    fn test_is_subset_of() {
+        let mut member = BloomFilter::new();
+        member.insert(b"alpha");
+        member.insert(b"beta");
+
+        // Aggregate contains the member plus extra features.
+        let mut aggregate = member.clone();
+        aggregate.insert(b"gamma");
+
+        assert!(member.is_subset_of(&aggregate));
+        assert!(!aggregate.is_subset_of(&member));
+
+        // Empty filter is a subset of anything.
+        assert!(BloomFilter::new().is_subset_of(&aggregate));
+
+        // Differently-sized filters are never subsets.
+        assert!(!member.is_subset_of(&BloomFilter::with_capacity(10_000, 0.01)));
+    }
+
+    #[test]
+    fn test_might_contain_all() {
+        let mut bloom = BloomFilter::new();
+        bloom.insert(b"one");
+        bloom.insert(b"two");
+
+        assert!(bloom.might_contain_all(&[b"one", b"two"]));
+        assert!(!bloom.might_contain_all(&[b"one", b"missing feature unlikely to collide"]));
+    }
+
+    #[test]
+    fn test_pluggable_hasher_name_and_clone() {
+        let bloom = BloomFilter::with_hasher(Box::new(ConstHasher));
+        assert_eq!(bloom.hasher_name(), "const-test");
+
+        // Clone must preserve the policy.
+        assert_eq!(bloom.clone().hasher_name(), "const-test");
+
+        // Different policies are not equal even when empty.
+        assert_ne!(bloom, BloomFilter::new());
+    }
+
+    #[test]
+    fn test_murmur_policy_matches_membership() {
+        // A filter backed by the Murmur policy must still answer membership
+        // correctly for what it has seen.
+        let mut bloom = BloomFilter::with_hasher(Box::new(MurmurBloomHasher));
+        assert_eq!(bloom.hasher_name(), "murmur3");
+
+        bloom.insert(b"alpha");
+        bloom.insert(b"beta");
+        assert!(bloom.contains(b"alpha"));
+        assert!(bloom.contains(b"beta"));
+        assert!(!bloom.contains(b"never-inserted-feature"));
+    }
+
+    #[test]
+    fn test_double_hashing_distributes_across_words() {
+        // Insert many distinct features and confirm the double-hashing indices
+        // reach every one of the 128 words of the default filter.
+        let mut bloom = BloomFilter::new();
+        for i in 0..2000u32 {
+            bloom.insert(format!("feature-{}", i).as_bytes());
+        }
+
+        let bytes = bloom.to_bytes();
+        let touched = bytes.chunks_exact(8).filter(|w| w.iter().any(|&b| b != 0)).count();
+        assert_eq!(touched, 128, "every word should be touched, got {}", touched);
+    }
+
+    #[test]
+    fn test_double_hashing_h2_never_zero() {
+        // A feature whose second hash happens to be even must still spread
+        // across distinct indices rather than collapsing onto h1.
+        let bloom = BloomFilter::new();
+        let indices = bloom.get_indices(b"collapse check");
+        let distinct = indices.iter().collect::<std::collections::HashSet<_>>().len();
+        assert!(distinct > 1, "indices should not collapse to a single slot");
+    }
+
+    #[test]
+    fn test_with_capacity_sizing() {
+        // Classic sizing example: n=10000, p=0.01 -> m ~ 95850 bits, k ~ 7.
+        let bloom = BloomFilter::with_capacity(10_000, 0.01);
+        assert_eq!(bloom.num_bits() % 64, 0, "m must be a multiple of 64");
+        assert!(bloom.num_bits() > BLOOM_SIZE_BITS);
+        assert_eq!(bloom.num_hashes(), 7);
+
+        // Round-trips through bytes at its dynamic size.
+        let restored = BloomFilter::from_bytes(&bloom.to_bytes());
+        assert_eq!(restored.num_bits(), bloom.num_bits());
+    }
+
+    #[test]
+    fn test_with_capacity_insert_contains() {
+        let mut bloom = BloomFilter::with_capacity(1000, 0.001);
+        bloom.insert(b"sized feature");
+        assert!(bloom.contains(b"sized feature"));
+    }
+
+    #[test]
+    fn test_try_jaccard_size_mismatch() {
+        let a = BloomFilter::new();
+        let b = BloomFilter::with_capacity(10_000, 0.01);
+
+        assert_eq!(a.try_jaccard_similarity(&b), Err(BloomError::SizeMismatch));
+        assert!(a.try_jaccard_similarity(&BloomFilter::new()).is_ok());
+    }
+
+    #[test]
+    fn test_optimal_defaults_on_degenerate_input() {
+        assert_eq!(optimal_num_bits(0, 0.01), BLOOM_SIZE_BITS);
+        assert_eq!(optimal_num_bits(1000, 0.0), BLOOM_SIZE_BITS);
+        assert_eq!(optimal_num_hashes(BLOOM_SIZE_BITS, 0), NUM_HASH_FUNCTIONS);
+    }
+
+    #[test]
+    fn test_counting_insert_and_remove() {
+        let mut counting = CountingBloomFilter::new();
+
+        counting.insert(b"feature");
+        let after_insert = counting.to_bloom();
+        assert!(after_insert.contains(b"feature"));
+
+        counting.remove(b"feature");
+        let after_remove = counting.to_bloom();
+        assert!(after_remove.is_empty(), "Removing the only feature should clear its slots");
+    }
+
+    #[test]
+    fn test_counting_multiplicity() {
+        let mut once = CountingBloomFilter::new();
+        let mut many = CountingBloomFilter::new();
+
+        once.insert(b"repeat");
+        for _ in 0..5 {
+            many.insert(b"repeat");
+        }
+
+        // Same slots are set, but weighted similarity reflects the multiplicity gap.
+        assert_eq!(once.to_bloom(), many.to_bloom());
+        let sim = once.weighted_similarity(&many);
+        assert!(sim < 1.0, "Different multiplicities should not be identical, got {}", sim);
+    }
+
+    #[test]
+    fn test_counting_weighted_identical() {
+        let mut counting = CountingBloomFilter::new();
+        counting.insert(b"a");
+        counting.insert(b"a");
+        counting.insert(b"b");
+
+        let sim = counting.weighted_similarity(&counting);
+        assert!((sim - 1.0).abs() < 0.001, "Identical filters should have J_w=1.0");
+    }
+
+    #[test]
+    fn test_counting_saturation() {
+        let mut counting = CountingBloomFilter::new();
+        for _ in 0..300 {
+            counting.insert(b"saturate");
+        }
+
+        // Counters must saturate at 255 rather than wrapping.
+        let indices = counting.get_indices(b"saturate");
+        for &idx in &indices {
+            assert_eq!(counting.counts[idx], 255);
+        }
+    }
+
     #[test]
     fn test_hash_distribution() {
         // Test that hash functions produce different indices
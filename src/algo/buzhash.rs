@@ -72,24 +72,65 @@ const BUZHASH_TABLE: [u64; 256] = [
     0x499ebda0789c1653, 0xe00f1bc3da6ef827, 0xda52b0894e17c3a5, 0xd5754e8bc2fa1d09,
 ];
 
-/// BuzHash window size (must be power of 2 for efficiency)
+/// Default BuzHash window size
 const WINDOW_SIZE: usize = 64;
 
+/// Odd multiplier mixing the byte-sum guard used for multiple-of-64 windows
+const SUM_MIX: u64 = 0x9e3779b97f4a7c15;
+
 /// BuzHash rolling hash state
+///
+/// The digest is a cyclic polynomial over the last `n` bytes: the i-th byte's
+/// table value is rotated left by its age, so after `n` updates the oldest
+/// contribution has rotated by `n mod 64` and can be cancelled. The eviction
+/// rotation `r = n % 64` and a per-byte rotated table `p[b] = rol(table[b], r)`
+/// are precomputed once, and each step is `rol(hash, 1) ^ p[byte_out] ^
+/// table[byte_in]`.
 pub struct BuzHash {
     hash: u64,
-    window: [u8; WINDOW_SIZE],
+    window: Vec<u8>,
     position: usize,
+    /// Per-byte eviction table `p[b] = rol(BUZHASH_TABLE[b], r)`.
+    evict_table: Box<[u64; 256]>,
+    /// `true` when `n % 64 == 0`, so the byte-sum guard is mixed into the digest.
+    degenerate: bool,
+    /// Running wrapping sum of the window's bytes (guard term, degenerate only).
+    sum: u64,
 }
 
 impl BuzHash {
-    /// Create a new BuzHash instance
+    /// Create a new BuzHash instance over the default 64-byte window
     #[inline]
     pub fn new() -> Self {
+        Self::with_window(WINDOW_SIZE)
+    }
+
+    /// Create a BuzHash over a window of `n` bytes (clamped to ≥ 1)
+    ///
+    /// The eviction rotation is `r = n % 64`, and the per-byte table is
+    /// pre-rotated by `r` so the outgoing byte cancels at exactly its age. When
+    /// `n` is a multiple of 64 (including the default), `r` is 0 and the
+    /// outgoing/incoming table values of a repeated byte would cancel, leaving
+    /// the digest a content-blind rotation. That degenerate case mixes in an
+    /// order-independent window byte-sum so a run of identical bytes no longer
+    /// collapses; it is rejected by neither constructor, but non-multiples of 64
+    /// avoid the guard entirely.
+    pub fn with_window(n: usize) -> Self {
+        let n = n.max(1);
+        let r = (n % 64) as u32;
+
+        let mut evict_table = Box::new([0u64; 256]);
+        for (b, slot) in evict_table.iter_mut().enumerate() {
+            *slot = Self::rol(BUZHASH_TABLE[b], r);
+        }
+
         Self {
             hash: 0,
-            window: [0; WINDOW_SIZE],
+            window: vec![0u8; n],
             position: 0,
+            evict_table,
+            degenerate: r == 0,
+            sum: 0,
         }
     }
 
@@ -97,8 +138,9 @@ impl BuzHash {
     #[inline]
     pub fn reset(&mut self) {
         self.hash = 0;
-        self.window = [0; WINDOW_SIZE];
+        self.window.iter_mut().for_each(|b| *b = 0);
         self.position = 0;
+        self.sum = 0;
     }
 
     /// Rotate left operation (crucial for BuzHash)
@@ -107,32 +149,52 @@ impl BuzHash {
         value.rotate_left(shift)
     }
 
+    /// Exposed digest, applying the byte-sum guard for multiple-of-64 windows
+    #[inline(always)]
+    fn digest(&self) -> u64 {
+        if self.degenerate {
+            self.hash ^ self.sum.wrapping_mul(SUM_MIX)
+        } else {
+            self.hash
+        }
+    }
+
     /// Update hash with a new byte (rolling window)
     #[inline]
     pub fn update(&mut self, byte_in: u8) -> u64 {
         let byte_out = self.window[self.position];
         self.window[self.position] = byte_in;
-        self.position = (self.position + 1) & (WINDOW_SIZE - 1); // Fast modulo for power of 2
+        self.position += 1;
+        if self.position == self.window.len() {
+            self.position = 0;
+        }
 
-        // Core BuzHash formula: R_next = rol(R_prev, 1) ⊕ RTL[byte_out] ⊕ RTL[byte_in]
+        // Core BuzHash formula: R_next = rol(R_prev, 1) ⊕ p[byte_out] ⊕ RTL[byte_in]
         self.hash = Self::rol(self.hash, 1)
-                    ^ Self::rol(BUZHASH_TABLE[byte_out as usize], WINDOW_SIZE as u32)
+                    ^ self.evict_table[byte_out as usize]
                     ^ BUZHASH_TABLE[byte_in as usize];
 
-        self.hash
+        if self.degenerate {
+            self.sum = self
+                .sum
+                .wrapping_add(byte_in as u64)
+                .wrapping_sub(byte_out as u64);
+        }
+
+        self.digest()
     }
 
     /// Get current hash value
     #[inline]
     pub fn hash(&self) -> u64 {
-        self.hash
+        self.digest()
     }
 
     /// Check if current hash triggers a feature point
     /// M is the modulus, dynamically calculated based on file size
     #[inline]
     pub fn is_trigger(&self, modulus: u64) -> bool {
-        self.hash % modulus == 0
+        self.digest() % modulus == 0
     }
 }
 
@@ -157,6 +219,28 @@ impl Default for BuzHash {
     }
 }
 
+impl super::rolling::RollingHash for BuzHash {
+    #[inline]
+    fn update(&mut self, byte_in: u8) -> u64 {
+        BuzHash::update(self, byte_in)
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        BuzHash::reset(self)
+    }
+
+    #[inline]
+    fn hash(&self) -> u64 {
+        BuzHash::hash(self)
+    }
+
+    #[inline]
+    fn is_trigger(&self, modulus: u64) -> bool {
+        BuzHash::is_trigger(self, modulus)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,6 +309,41 @@ mod tests {
         assert!(diff_bits > 10, "Avalanche effect insufficient: {} bits different", diff_bits);
     }
 
+    #[test]
+    fn test_custom_window_evicts_at_age() {
+        // With a non-multiple-of-64 window the outgoing byte is rotated by its
+        // true age, so bytes beyond the window must not affect the digest.
+        let mut a = BuzHash::with_window(40);
+        let mut b = BuzHash::with_window(40);
+        for &byte in b"................The quick brown fox jumps over the lazy dog" {
+            a.update(byte);
+        }
+        for &byte in b"xxxxxxxxxxxxxxxxThe quick brown fox jumps over the lazy dog" {
+            b.update(byte);
+        }
+        // The shared suffix is 43 bytes, so the 40-byte window coincides in both.
+        assert_eq!(a.hash(), b.hash(), "stale bytes leaked past the window");
+    }
+
+    #[test]
+    fn test_multiple_of_64_does_not_collapse() {
+        // A window full of one repeated byte leaves the cyclic part of the
+        // digest at all-zero or all-one bits whichever byte it is; the byte-sum
+        // guard keeps runs of different bytes apart.
+        let mut hash = BuzHash::with_window(64);
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..200 {
+            seen.insert(hash.update(0x5A));
+        }
+        assert!(seen.len() > 1, "digest collapsed on a constant run");
+
+        let mut other = BuzHash::with_window(64);
+        for _ in 0..200 {
+            other.update(0xA5);
+        }
+        assert_ne!(hash.hash(), other.hash(), "constant runs of different bytes collapsed");
+    }
+
     #[test]
     fn test_trigger_detection() {
         let mut hash = BuzHash::new();
@@ -0,0 +1,218 @@
+//! Content-Defined Chunking (CDC) built on BuzHash triggers
+//!
+//! Turns a byte slice into variable-length chunks whose boundaries follow the
+//! content rather than a fixed stride, so inserting or deleting bytes only
+//! reshapes the chunks around the edit instead of shifting every boundary
+//! downstream. Each emitted [`Chunk`] carries its offset, length, and the final
+//! BuzHash digest, ready to feed into the bloom/structural machinery for
+//! deduplication or similarity.
+//!
+//! Boundaries use a normalized dual-mask scheme (as in FastCDC-style cutters):
+//! bytes before `min_size` never cut, a cut is forced at `max_size`, and in
+//! between a boundary is declared when `hash & mask == 0`. A stricter mask
+//! (more bits, so cuts are rarer) applies before the target average size and a
+//! looser mask after it, which tightens the chunk-size distribution compared to
+//! a single modulus.
+
+use super::buzhash::BuzHash;
+use super::rolling::RollingHash;
+
+/// Default minimum chunk size in bytes
+pub const DEFAULT_MIN_SIZE: usize = 2 * 1024;
+
+/// Default target average chunk size in bytes
+pub const DEFAULT_AVG_SIZE: usize = 8 * 1024;
+
+/// Default maximum chunk size in bytes
+pub const DEFAULT_MAX_SIZE: usize = 64 * 1024;
+
+/// A content-defined chunk: a span of the input plus its rolling-hash digest
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chunk {
+    /// Byte offset of the chunk within the input
+    pub offset: usize,
+    /// Length of the chunk in bytes
+    pub length: usize,
+    /// Rolling-hash digest after the chunk's last byte; it covers only the
+    /// final window (64 bytes for the default [`BuzHash`]) of the chunk
+    pub digest: u64,
+}
+
+/// Iterator that cuts a byte slice into content-defined chunks
+///
+/// Generic over the [`RollingHash`] backend; defaults to [`BuzHash`]. The
+/// hasher is [`reset`](RollingHash::reset) at each chunk boundary so the digest
+/// stored on a [`Chunk`] covers only that chunk's bytes.
+pub struct CdcChunker<'a, H = BuzHash> {
+    data: &'a [u8],
+    pos: usize,
+    min_size: usize,
+    max_size: usize,
+    normal_size: usize,
+    mask_s: u64,
+    mask_l: u64,
+    hash: H,
+}
+
+impl<'a> CdcChunker<'a, BuzHash> {
+    /// Create a chunker with the default size parameters, backed by [`BuzHash`]
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::with_sizes(data, DEFAULT_MIN_SIZE, DEFAULT_AVG_SIZE, DEFAULT_MAX_SIZE)
+    }
+
+    /// Create a [`BuzHash`]-backed chunker with explicit `min`/`avg`/`max` sizes
+    ///
+    /// Sizes are clamped into a sane order (`1 <= min <= avg <= max`). The two
+    /// boundary masks are derived from the average: the strict mask has one more
+    /// significant bit than the average's log2 (rarer cuts), the loose mask one
+    /// fewer (more frequent cuts).
+    pub fn with_sizes(data: &'a [u8], min: usize, avg: usize, max: usize) -> Self {
+        Self::with_sizes_in(data, min, avg, max, BuzHash::new())
+    }
+
+    /// Cut the whole input eagerly into a vector of chunks
+    pub fn chunks(data: &'a [u8]) -> Vec<Chunk> {
+        Self::new(data).collect()
+    }
+}
+
+impl<'a, H: RollingHash> CdcChunker<'a, H> {
+    /// Create a chunker with the default sizes over a caller-supplied backend
+    pub fn with_hash(data: &'a [u8], hash: H) -> Self {
+        Self::with_sizes_in(data, DEFAULT_MIN_SIZE, DEFAULT_AVG_SIZE, DEFAULT_MAX_SIZE, hash)
+    }
+
+    /// Create a chunker with explicit sizes over a caller-supplied backend
+    ///
+    /// See [`with_sizes`](CdcChunker::with_sizes) for how the boundary masks are
+    /// derived from `avg`.
+    pub fn with_sizes_in(data: &'a [u8], min: usize, avg: usize, max: usize, hash: H) -> Self {
+        let min_size = min.max(1);
+        let avg = avg.max(min_size);
+        let max_size = max.max(avg);
+
+        // Floor log2 of the average; masks bracket it by one bit either side.
+        let bits = (usize::BITS - 1 - avg.max(2).leading_zeros()) as u32;
+        let mask_s = (1u64 << (bits + 1)) - 1;
+        let mask_l = (1u64 << bits.saturating_sub(1)) - 1;
+
+        Self {
+            data,
+            pos: 0,
+            min_size,
+            max_size,
+            normal_size: avg,
+            mask_s,
+            mask_l,
+            hash,
+        }
+    }
+}
+
+impl<H: RollingHash> Iterator for CdcChunker<'_, H> {
+    type Item = Chunk;
+
+    fn next(&mut self) -> Option<Chunk> {
+        let n = self.data.len();
+        if self.pos >= n {
+            return None;
+        }
+
+        let start = self.pos;
+        self.hash.reset();
+
+        // A trailing remainder smaller than the minimum is emitted whole.
+        if n - start <= self.min_size {
+            for &b in &self.data[start..n] {
+                self.hash.update(b);
+            }
+            self.pos = n;
+            return Some(Chunk {
+                offset: start,
+                length: n - start,
+                digest: self.hash.hash(),
+            });
+        }
+
+        // Warm the rolling hash over the guaranteed-minimum prefix (no cuts).
+        let mut i = start;
+        while i < start + self.min_size {
+            self.hash.update(self.data[i]);
+            i += 1;
+        }
+
+        let hardcap = (start + self.max_size).min(n);
+        while i < hardcap {
+            let h = self.hash.update(self.data[i]);
+            i += 1;
+
+            // Strict mask before the average size, loose mask after it.
+            let mask = if i - start <= self.normal_size {
+                self.mask_s
+            } else {
+                self.mask_l
+            };
+            if h & mask == 0 {
+                break;
+            }
+        }
+
+        self.pos = i;
+        Some(Chunk {
+            offset: start,
+            length: i - start,
+            digest: self.hash.hash(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_cover_input_contiguously() {
+        let data: Vec<u8> = (0..200_000u32).map(|i| ((i * 73 + 11) % 256) as u8).collect();
+        let chunks = CdcChunker::chunks(&data);
+
+        assert!(!chunks.is_empty());
+
+        // Chunks must tile the input with no gaps or overlaps.
+        let mut expected = 0;
+        for chunk in &chunks {
+            assert_eq!(chunk.offset, expected);
+            expected += chunk.length;
+        }
+        assert_eq!(expected, data.len());
+    }
+
+    #[test]
+    fn test_chunk_sizes_respect_bounds() {
+        let data: Vec<u8> = (0..500_000u32).map(|i| ((i * 131 + 7) % 256) as u8).collect();
+        let chunks = CdcChunker::chunks(&data);
+
+        // Every chunk but the last honors the min/max window.
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert!(chunk.length >= DEFAULT_MIN_SIZE);
+            assert!(chunk.length <= DEFAULT_MAX_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_boundaries_are_content_defined() {
+        // Prepending a byte should leave most downstream boundaries intact,
+        // unlike fixed-size chunking.
+        let base: Vec<u8> = (0..300_000u32).map(|i| ((i * 97 + 3) % 256) as u8).collect();
+        let mut shifted = vec![0xAAu8];
+        shifted.extend_from_slice(&base);
+
+        let a = CdcChunker::chunks(&base);
+        let b = CdcChunker::chunks(&shifted);
+
+        // Collect digests appearing in both cuttings; CDC should re-sync so many
+        // interior chunks match despite the shift.
+        let set_a: std::collections::HashSet<u64> = a.iter().map(|c| c.digest).collect();
+        let shared = b.iter().filter(|c| set_a.contains(&c.digest)).count();
+        assert!(shared > b.len() / 2, "CDC failed to re-sync after a shift");
+    }
+}
@@ -3,11 +3,15 @@
 //! Calculates entropy for blocks to create structural fingerprint.
 //! Uses AVX2 SIMD when available for maximum performance.
 
+use rayon::prelude::*;
 use std::f32;
 
 /// Minimum block size for entropy calculation
 pub const MIN_BLOCK_SIZE: usize = 64;
 
+/// Inputs at or above this size use the parallel entropy scan (1MB)
+const PARALLEL_ENTROPY_THRESHOLD: usize = 1_048_576;
+
 /// Target signature length for structural hash (in bytes)
 /// This ensures the structural signature remains compact (~128-256 blocks)
 /// regardless of file size, solving the O(N^2) complexity issue.
@@ -45,11 +49,24 @@ pub fn calculate_entropy(block: &[u8]) -> f32 {
         frequencies[byte as usize] += 1;
     }
 
-    let block_len = block.len() as f32;
+    entropy_from_histogram(&frequencies, block.len())
+}
+
+/// Reduce a byte-frequency histogram to Shannon entropy
+///
+/// Shared by the scalar and SIMD paths so both produce bit-identical results:
+/// the histogram counts are order-independent and the reduction loop is the
+/// single source of truth for the `-Σ p·log2(p)` sum.
+#[inline]
+pub(crate) fn entropy_from_histogram(frequencies: &[u32; 256], block_len: usize) -> f32 {
+    if block_len == 0 {
+        return 0.0;
+    }
+
+    let block_len = block_len as f32;
     let mut entropy = 0.0f32;
 
-    // Calculate entropy using fast log2
-    for &freq in &frequencies {
+    for &freq in frequencies.iter() {
         if freq > 0 {
             let probability = freq as f32 / block_len;
             entropy -= probability * fast_log2(probability);
@@ -59,13 +76,60 @@ pub fn calculate_entropy(block: &[u8]) -> f32 {
     entropy
 }
 
+/// Number of disjoint histogram tables used to break the store-to-load chain
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+const HISTOGRAM_TABLES: usize = 4;
+
 /// Calculate entropy using SIMD (AVX2) when available
 /// Falls back to scalar implementation on unsupported platforms
+///
+/// Builds the byte histogram with multiple disjoint frequency tables so
+/// increments on adjacent bytes hit different arrays, breaking the long
+/// store-to-load dependency chain of the single-table scalar loop. The tables
+/// are summed 8-wide with `_mm256_add_epi32` and fed into the shared
+/// `-Σ p·log2(p)` reduction, producing a value bit-identical to
+/// [`calculate_entropy`].
 #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
 pub fn calculate_entropy_simd(block: &[u8]) -> f32 {
-    calculate_entropy(block) // Placeholder for full SIMD implementation
-    // Full SIMD implementation would use _mm256_* intrinsics
-    // but requires careful handling of horizontal operations
+    use std::arch::x86_64::*;
+
+    if block.is_empty() {
+        return 0.0;
+    }
+
+    // Stripe consecutive bytes across the disjoint tables, round-robin.
+    let mut tables = [[0u32; 256]; HISTOGRAM_TABLES];
+    let chunks = block.chunks_exact(HISTOGRAM_TABLES);
+    let remainder = chunks.remainder();
+    for chunk in chunks {
+        for (t, &byte) in chunk.iter().enumerate() {
+            tables[t][byte as usize] += 1;
+        }
+    }
+    // Process the tail scalar into the first table.
+    for &byte in remainder {
+        tables[0][byte as usize] += 1;
+    }
+
+    // Vector-sum the tables elementwise into a final histogram, 8 bins at a time.
+    let mut frequencies = [0u32; 256];
+    // SAFETY: guarded by `target_feature = "avx2"`, so AVX2 is available; the
+    // 256-entry arrays are read and written 8 lanes at a time with unaligned
+    // loads/stores, always in bounds.
+    unsafe {
+        let mut i = 0;
+        while i < 256 {
+            let mut acc = _mm256_loadu_si256(tables[0].as_ptr().add(i) as *const __m256i);
+            for table in tables.iter().skip(1) {
+                let v = _mm256_loadu_si256(table.as_ptr().add(i) as *const __m256i);
+                acc = _mm256_add_epi32(acc, v);
+            }
+            _mm256_storeu_si256(frequencies.as_mut_ptr().add(i) as *mut __m256i, acc);
+            i += 8;
+        }
+    }
+
+    entropy_from_histogram(&frequencies, block.len())
 }
 
 #[cfg(not(all(target_arch = "x86_64", target_feature = "avx2")))]
@@ -73,6 +137,127 @@ pub fn calculate_entropy_simd(block: &[u8]) -> f32 {
     calculate_entropy(block)
 }
 
+/// Build a reference distribution modeling "typical" text-like data
+///
+/// Printable ASCII, and especially letters and spaces, carry more weight than
+/// control or high bytes. Every bucket keeps a small floor so the log table is
+/// always finite. Callers who know their domain can supply their own
+/// distribution to [`reference_log_table`].
+pub fn default_reference_distribution() -> [f64; 256] {
+    let mut weights = [1.0f64; 256];
+    for (i, w) in weights.iter_mut().enumerate() {
+        let b = i as u8;
+        if (0x20..=0x7e).contains(&b) {
+            *w += 20.0;
+        }
+        if b.is_ascii_alphabetic() {
+            *w += 60.0;
+        }
+        if b == b' ' {
+            *w += 160.0;
+        }
+        if b == b'\n' {
+            *w += 10.0;
+        }
+    }
+
+    let total: f64 = weights.iter().sum();
+    for w in weights.iter_mut() {
+        *w /= total;
+    }
+    weights
+}
+
+/// Precompute `ref_log[i] = -log2(ref_prob[i])` for a reference distribution
+///
+/// Zero-probability buckets get a fixed finite surprise of 8 bits so the table
+/// never contains infinities. Rare symbols with a nonzero probability can score
+/// higher than that.
+pub fn reference_log_table(ref_prob: &[f64; 256]) -> [f32; 256] {
+    let mut table = [0f32; 256];
+    for (i, &p) in ref_prob.iter().enumerate() {
+        table[i] = if p > 0.0 {
+            (-p.log2()) as f32
+        } else {
+            // Missing symbol: 8 bits, the surprise of a uniform byte.
+            8.0
+        };
+    }
+    table
+}
+
+/// Cross-entropy of a block against a reference log table
+///
+/// `H_cross = Σ_i (count_i / len) · ref_log[i]`, measuring how "surprising" the
+/// block is relative to the reference distribution. Unlike Shannon entropy this
+/// distinguishes data that merely shares a byte histogram *shape* from data that
+/// matches the reference itself.
+pub fn cross_entropy(block: &[u8], ref_log: &[f32; 256]) -> f32 {
+    if block.is_empty() {
+        return 0.0;
+    }
+
+    let mut frequencies = [0u32; 256];
+    for &byte in block {
+        frequencies[byte as usize] += 1;
+    }
+
+    let len = block.len() as f32;
+    let mut h_cross = 0.0f32;
+    for (i, &freq) in frequencies.iter().enumerate() {
+        if freq > 0 {
+            h_cross += (freq as f32 / len) * ref_log[i];
+        }
+    }
+    h_cross
+}
+
+/// Cross-entropy of a byte-frequency histogram against a reference log table
+///
+/// Histogram-driven companion to [`cross_entropy`], letting incremental callers
+/// (e.g. the streaming hasher) accumulate counts and reduce once per block
+/// instead of retaining the block's bytes.
+#[inline]
+pub(crate) fn cross_entropy_from_histogram(
+    frequencies: &[u32; 256],
+    block_len: usize,
+    ref_log: &[f32; 256],
+) -> f32 {
+    if block_len == 0 {
+        return 0.0;
+    }
+
+    let len = block_len as f32;
+    let mut h_cross = 0.0f32;
+    for (i, &freq) in frequencies.iter().enumerate() {
+        if freq > 0 {
+            h_cross += (freq as f32 / len) * ref_log[i];
+        }
+    }
+    h_cross
+}
+
+/// Structural vector using cross-entropy blocks instead of Shannon entropy
+///
+/// Mirrors [`generate_structural_vector`] block-for-block but quantizes the
+/// cross-entropy against `ref_log` with the same nibble scheme, so the two
+/// descriptors are interchangeable in shape and comparison.
+pub fn generate_structural_vector_cross(data: &[u8], ref_log: &[f32; 256]) -> Vec<u8> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let block_size = std::cmp::max(MIN_BLOCK_SIZE, data.len() / TARGET_SIGNATURE_LEN);
+
+    let num_blocks = data.len().div_ceil(block_size);
+    let mut nibbles = Vec::with_capacity(num_blocks);
+    for chunk in data.chunks(block_size) {
+        nibbles.push(quantize_entropy(cross_entropy(chunk, ref_log)));
+    }
+
+    pack_nibbles(&nibbles)
+}
+
 /// Quantize entropy value to 4-bit nibble (0-15)
 /// Q = ⌊H(B) × 1.875⌋ mod 16
 #[inline]
@@ -98,7 +283,12 @@ pub fn generate_structural_vector(data: &[u8]) -> Vec<u8> {
         data.len() / TARGET_SIGNATURE_LEN
     );
 
-    let num_blocks = (data.len() + block_size - 1) / block_size;
+    // Large inputs scan their independent blocks in parallel.
+    if data.len() >= PARALLEL_ENTROPY_THRESHOLD {
+        return generate_structural_vector_parallel(data);
+    }
+
+    let num_blocks = data.len().div_ceil(block_size);
     let mut nibbles = Vec::with_capacity(num_blocks);
 
     // Process blocks and calculate entropy for each
@@ -112,10 +302,31 @@ pub fn generate_structural_vector(data: &[u8]) -> Vec<u8> {
     pack_nibbles(&nibbles)
 }
 
+/// Parallel variant of [`generate_structural_vector`]
+///
+/// Entropy blocks are positionally independent, so each is scanned on a rayon
+/// worker; the per-block nibbles are collected back in original order (a simple
+/// ordered `map`/`collect`) before packing, making the output byte-for-byte
+/// identical to the sequential path.
+pub fn generate_structural_vector_parallel(data: &[u8]) -> Vec<u8> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let block_size = std::cmp::max(MIN_BLOCK_SIZE, data.len() / TARGET_SIGNATURE_LEN);
+
+    let nibbles: Vec<u8> = data
+        .par_chunks(block_size)
+        .map(|chunk| quantize_entropy(calculate_entropy(chunk)))
+        .collect();
+
+    pack_nibbles(&nibbles)
+}
+
 /// Pack nibbles (4-bit values) into bytes
 /// Two nibbles are packed per byte: [high_nibble, low_nibble]
 #[inline]
-fn pack_nibbles(nibbles: &[u8]) -> Vec<u8> {
+pub(crate) fn pack_nibbles(nibbles: &[u8]) -> Vec<u8> {
     let mut packed = Vec::with_capacity((nibbles.len() + 1) / 2);
 
     for pair in nibbles.chunks(2) {
@@ -244,6 +455,68 @@ mod tests {
         assert_eq!(structural.len(), 8);
     }
 
+    #[test]
+    fn test_cross_entropy_distinguishes_distributions() {
+        let ref_log = reference_log_table(&default_reference_distribution());
+
+        // Text-like data should be less "surprising" than high-entropy bytes.
+        let text = b"the quick brown fox jumps over the lazy dog ".repeat(16);
+        let random: Vec<u8> = (0..text.len()).map(|i| ((i * 131 + 17) % 256) as u8).collect();
+
+        let h_text = cross_entropy(&text, &ref_log);
+        let h_random = cross_entropy(&random, &ref_log);
+        assert!(h_text < h_random, "text {} should be less surprising than random {}", h_text, h_random);
+    }
+
+    #[test]
+    fn test_cross_entropy_structural_shape() {
+        let ref_log = reference_log_table(&default_reference_distribution());
+        let data = vec![b'a'; 2048];
+
+        let shannon = generate_structural_vector(&data);
+        let cross = generate_structural_vector_cross(&data, &ref_log);
+        // Same block layout -> same packed length, different descriptor values.
+        assert_eq!(shannon.len(), cross.len());
+    }
+
+    #[test]
+    fn test_simd_entropy_bit_identical() {
+        // Random-ish, all-equal, and two-valued blocks must all match
+        // the scalar entropy exactly (same histogram, same reduction).
+        let random: Vec<u8> = (0..4096u32).map(|i| ((i * 101 + 7) % 256) as u8).collect();
+        let uniform = vec![0xABu8; 4096];
+        let mut two_valued = vec![0u8; 4096];
+        for b in two_valued.iter_mut().step_by(3) {
+            *b = 0xFF;
+        }
+
+        for block in [random.as_slice(), uniform.as_slice(), two_valued.as_slice()] {
+            assert_eq!(
+                calculate_entropy(block).to_bits(),
+                calculate_entropy_simd(block).to_bits(),
+                "SIMD entropy must be bit-identical to scalar"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        // A few megabytes of varied data exercises the parallel block scan.
+        let data: Vec<u8> = (0..3_000_000u32).map(|i| ((i * 37) % 256) as u8).collect();
+
+        let sequential = {
+            let block_size = std::cmp::max(MIN_BLOCK_SIZE, data.len() / TARGET_SIGNATURE_LEN);
+            let nibbles: Vec<u8> = data
+                .chunks(block_size)
+                .map(|c| quantize_entropy(calculate_entropy(c)))
+                .collect();
+            pack_nibbles(&nibbles)
+        };
+
+        let parallel = generate_structural_vector_parallel(&data);
+        assert_eq!(sequential, parallel, "parallel output must match sequential");
+    }
+
     #[test]
     fn test_levenshtein_identical() {
         let a = vec![1, 2, 3, 4, 5];
@@ -2,8 +2,21 @@
 
 pub mod bloom;
 pub mod buzhash;
+pub mod chunker;
 pub mod entropy;
+pub mod murmur;
+pub mod rolling;
 
-pub use bloom::{BloomFilter, BloomFilterBuilder, BLOOM_SIZE_BYTES};
+pub use bloom::{
+    BloomError, BloomFilter, BloomFilterBuilder, BloomHasher, CountingBloomFilter, FxBloomHasher,
+    MurmurBloomHasher, BLOOM_SIZE_BYTES,
+};
+pub use murmur::murmur3_x64_128;
 pub use buzhash::{BuzHash, calculate_modulus};
-pub use entropy::{calculate_entropy, generate_structural_vector, structural_similarity};
+pub use chunker::{CdcChunker, Chunk};
+pub use rolling::{RabinKarp, RollingHash};
+pub use entropy::{
+    calculate_entropy, cross_entropy, default_reference_distribution,
+    generate_structural_vector, generate_structural_vector_cross,
+    generate_structural_vector_parallel, reference_log_table, structural_similarity,
+};
@@ -0,0 +1,130 @@
+//! MurmurHash3 x64 128-bit
+//!
+//! The 64-bit-architecture flavor of MurmurHash3: two 64-bit lanes mixed with
+//! the constants `c1`/`c2`, block rotates, and the `fmix64` finalizer. A single
+//! call yields 128 bits of non-cryptographic hash, whose two halves seed the
+//! Kirsch–Mitzenmacher double hashing used by [`MurmurBloomHasher`] so each
+//! inserted feature needs only one hash instead of `k` independent passes.
+//!
+//! [`MurmurBloomHasher`]: super::bloom::MurmurBloomHasher
+
+const C1: u64 = 0x87c3_7b91_1142_53d5;
+const C2: u64 = 0x4cf5_ad43_2745_937f;
+
+/// MurmurHash3 finalization mix for a 64-bit lane
+#[inline(always)]
+fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    k ^= k >> 33;
+    k
+}
+
+/// Compute the MurmurHash3 x64 128-bit digest of `data` under `seed`
+///
+/// Returns the two 64-bit halves `(h1, h2)`. Always reads bytes little-endian,
+/// so the result is stable across architectures and safe to serialize.
+pub fn murmur3_x64_128(data: &[u8], seed: u64) -> (u64, u64) {
+    let mut h1 = seed;
+    let mut h2 = seed;
+
+    let nblocks = data.len() / 16;
+    for i in 0..nblocks {
+        let base = i * 16;
+        let mut k1 = u64::from_le_bytes(data[base..base + 8].try_into().unwrap());
+        let mut k2 = u64::from_le_bytes(data[base + 8..base + 16].try_into().unwrap());
+
+        k1 = k1.wrapping_mul(C1);
+        k1 = k1.rotate_left(31);
+        k1 = k1.wrapping_mul(C2);
+        h1 ^= k1;
+        h1 = h1.rotate_left(27);
+        h1 = h1.wrapping_add(h2);
+        h1 = h1.wrapping_mul(5).wrapping_add(0x52dc_e729);
+
+        k2 = k2.wrapping_mul(C2);
+        k2 = k2.rotate_left(33);
+        k2 = k2.wrapping_mul(C1);
+        h2 ^= k2;
+        h2 = h2.rotate_left(31);
+        h2 = h2.wrapping_add(h1);
+        h2 = h2.wrapping_mul(5).wrapping_add(0x3849_5ab5);
+    }
+
+    // Tail: the up-to-15 bytes that did not fill a final 16-byte block.
+    let tail = &data[nblocks * 16..];
+    let mut k1 = 0u64;
+    let mut k2 = 0u64;
+
+    if tail.len() >= 15 { k2 ^= (tail[14] as u64) << 48; }
+    if tail.len() >= 14 { k2 ^= (tail[13] as u64) << 40; }
+    if tail.len() >= 13 { k2 ^= (tail[12] as u64) << 32; }
+    if tail.len() >= 12 { k2 ^= (tail[11] as u64) << 24; }
+    if tail.len() >= 11 { k2 ^= (tail[10] as u64) << 16; }
+    if tail.len() >= 10 { k2 ^= (tail[9] as u64) << 8; }
+    if tail.len() >= 9 {
+        k2 ^= tail[8] as u64;
+        k2 = k2.wrapping_mul(C2);
+        k2 = k2.rotate_left(33);
+        k2 = k2.wrapping_mul(C1);
+        h2 ^= k2;
+    }
+
+    if tail.len() >= 8 { k1 ^= (tail[7] as u64) << 56; }
+    if tail.len() >= 7 { k1 ^= (tail[6] as u64) << 48; }
+    if tail.len() >= 6 { k1 ^= (tail[5] as u64) << 40; }
+    if tail.len() >= 5 { k1 ^= (tail[4] as u64) << 32; }
+    if tail.len() >= 4 { k1 ^= (tail[3] as u64) << 24; }
+    if tail.len() >= 3 { k1 ^= (tail[2] as u64) << 16; }
+    if tail.len() >= 2 { k1 ^= (tail[1] as u64) << 8; }
+    if !tail.is_empty() {
+        k1 ^= tail[0] as u64;
+        k1 = k1.wrapping_mul(C1);
+        k1 = k1.rotate_left(31);
+        k1 = k1.wrapping_mul(C2);
+        h1 ^= k1;
+    }
+
+    // Finalization.
+    let len = data.len() as u64;
+    h1 ^= len;
+    h2 ^= len;
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    h1 = fmix64(h1);
+    h2 = fmix64(h2);
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+
+    (h1, h2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_input_is_seed_invariant_at_zero() {
+        assert_eq!(murmur3_x64_128(b"", 0), (0, 0));
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(murmur3_x64_128(data, 0), murmur3_x64_128(data, 0));
+    }
+
+    #[test]
+    fn test_tail_lengths_distinct() {
+        // Inputs straddling the 16-byte block boundary must all hash distinctly.
+        let base = b"0123456789abcdef"; // one full block
+        let mut seen = std::collections::HashSet::new();
+        for extra in 0..16 {
+            let mut v = base.to_vec();
+            v.extend(std::iter::repeat(b'z').take(extra));
+            assert!(seen.insert(murmur3_x64_128(&v, 0)), "tail {} collided", extra);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Rolling-hash abstraction and backends
+//!
+//! A rolling hash maintains a digest over a sliding window of the most recent
+//! bytes, updating in O(1) as the window advances. LavinHash uses it to pick
+//! content-defined feature points: wherever the digest hits a trigger the
+//! surrounding bytes become a fingerprint feature, so the selection follows the
+//! content instead of a fixed stride.
+//!
+//! The [`RollingHash`] trait lets the chunker and feature-selection code work
+//! over any backend. Two are provided: [`BuzHash`](super::buzhash::BuzHash), a
+//! cyclic-polynomial hash with strong avalanche behavior, and [`RabinKarp`], a
+//! polynomial hash whose collision/boundary profile suits data with long
+//! low-entropy runs. Callers pick whichever matches their data.
+
+/// A sliding-window hash that updates in O(1) per byte
+///
+/// Feeding a byte with [`update`](RollingHash::update) shifts the window by one
+/// position and returns the new digest; [`is_trigger`](RollingHash::is_trigger)
+/// reports whether that digest selects a feature point.
+pub trait RollingHash {
+    /// Roll the window forward by one byte and return the updated digest
+    fn update(&mut self, byte_in: u8) -> u64;
+
+    /// Clear all state, as if freshly constructed
+    fn reset(&mut self);
+
+    /// Current digest without advancing the window
+    fn hash(&self) -> u64;
+
+    /// Whether the current digest selects a feature point for `modulus`
+    ///
+    /// A feature is triggered when the digest is congruent to zero, so roughly
+    /// one window in `modulus` cuts.
+    #[inline]
+    fn is_trigger(&self, modulus: u64) -> bool {
+        self.hash() % modulus == 0
+    }
+}
+
+/// Odd multiplier for the Rabin–Karp polynomial (wrapping arithmetic over 2^64)
+const RK_BASE: u64 = 0x100000001b3;
+
+/// Rabin–Karp rolling hash over a fixed window of `n` bytes
+///
+/// Maintains `H = b_1·a^(n-1) + b_2·a^(n-2) + … + b_n` where `a` is [`RK_BASE`]
+/// and all arithmetic wraps modulo `2^64`, so no explicit modulus is needed.
+/// Rolling one byte in (and the oldest byte out) is
+/// `H = H·a + b_in − b_out·a^n`, with `a^n` precomputed once at construction.
+pub struct RabinKarp {
+    hash: u64,
+    window: Vec<u8>,
+    position: usize,
+    /// `a^n`, the weight the oldest byte carries before it leaves the window.
+    base_pow_n: u64,
+}
+
+impl RabinKarp {
+    /// Create a Rabin–Karp hash over a window of `n` bytes (clamped to ≥ 1)
+    pub fn with_window(n: usize) -> Self {
+        let n = n.max(1);
+        let base_pow_n = RK_BASE.wrapping_pow(n as u32);
+        Self {
+            hash: 0,
+            window: vec![0u8; n],
+            position: 0,
+            base_pow_n,
+        }
+    }
+
+    /// Create a Rabin–Karp hash with the default 64-byte window
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_window(64)
+    }
+}
+
+impl RollingHash for RabinKarp {
+    #[inline]
+    fn update(&mut self, byte_in: u8) -> u64 {
+        let byte_out = self.window[self.position];
+        self.window[self.position] = byte_in;
+        self.position += 1;
+        if self.position == self.window.len() {
+            self.position = 0;
+        }
+
+        // H = H·a + b_in − b_out·a^n  (wrapping over u64)
+        self.hash = self
+            .hash
+            .wrapping_mul(RK_BASE)
+            .wrapping_add(byte_in as u64)
+            .wrapping_sub((byte_out as u64).wrapping_mul(self.base_pow_n));
+
+        self.hash
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        self.hash = 0;
+        self.window.iter_mut().for_each(|b| *b = 0);
+        self.position = 0;
+    }
+
+    #[inline]
+    fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl Default for RabinKarp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::buzhash::BuzHash;
+
+    #[test]
+    fn test_rabin_karp_deterministic() {
+        let data = b"Hello, World! This is a test.";
+        let mut a = RabinKarp::new();
+        let mut b = RabinKarp::new();
+        for &byte in data {
+            a.update(byte);
+            b.update(byte);
+        }
+        assert_eq!(a.hash(), b.hash(), "Hash should be deterministic");
+    }
+
+    #[test]
+    fn test_rabin_karp_window_evicts() {
+        // After the window has fully turned over, only the last `n` bytes matter.
+        let mut a = RabinKarp::with_window(8);
+        let mut b = RabinKarp::with_window(8);
+        for &byte in b"XXXXXXXXabcdefgh" {
+            a.update(byte);
+        }
+        for &byte in b"YYYYYYYYabcdefgh" {
+            b.update(byte);
+        }
+        assert_eq!(a.hash(), b.hash(), "Bytes beyond the window must not persist");
+    }
+
+    #[test]
+    fn test_both_backends_detect_triggers() {
+        // Both backends should select feature points on the same corpus.
+        let data: Vec<u8> = (0..4096u32).map(|i| ((i * 131 + 7) % 256) as u8).collect();
+        let modulus = 32;
+
+        let mut buz = BuzHash::new();
+        let mut rk = RabinKarp::new();
+        let (mut buz_triggers, mut rk_triggers) = (0usize, 0usize);
+
+        for &byte in &data {
+            buz.update(byte);
+            rk.update(byte);
+            if buz.is_trigger(modulus) {
+                buz_triggers += 1;
+            }
+            if rk.is_trigger(modulus) {
+                rk_triggers += 1;
+            }
+        }
+
+        assert!(buz_triggers > 0, "BuzHash detected no triggers");
+        assert!(rk_triggers > 0, "Rabin–Karp detected no triggers");
+    }
+}
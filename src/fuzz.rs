@@ -0,0 +1,135 @@
+//! Fuzzing entry points and robustness smoke tests
+//!
+//! The FFI surface ([`crate::hf_hash`], [`crate::hf_compare`]) parses
+//! attacker-controlled buffers through [`FuzzyFingerprint::from_bytes`] and the
+//! raw-pointer helpers in [`crate::utils`], so malformed or truncated input must
+//! never panic, read out of bounds, or trip UB — it must surface an error or a
+//! clamped score instead.
+//!
+//! The reusable checks below take `&[u8]` so a `cargo-fuzz`/libfuzzer target can
+//! call them directly (e.g. `fuzz_target!(|data: &[u8]| lavinhash::fuzz::parse(data))`),
+//! while the `#[cfg(test)]` smoke tests drive the same entry points with random
+//! and edge-case inputs under the normal test harness.
+
+use crate::model::FuzzyFingerprint;
+use crate::{compare_hashes, generate_hash, HashConfig};
+
+/// Round-trip invariant: `generate_hash -> to_bytes -> from_bytes` is stable
+///
+/// For empty input it only checks that `generate_hash` rejects it. For any
+/// non-empty input the parsed fingerprint must equal the
+/// original and re-serialize to the same bytes.
+pub fn roundtrip(data: &[u8]) {
+    if data.is_empty() {
+        assert!(generate_hash(data, &HashConfig::default()).is_err());
+        return;
+    }
+
+    let fp = generate_hash(data, &HashConfig::default()).expect("non-empty input must hash");
+    let bytes = fp.to_bytes();
+    let parsed = FuzzyFingerprint::from_bytes(&bytes).expect("own output must parse");
+
+    assert_eq!(fp, parsed, "fingerprint changed across serialization");
+    assert_eq!(bytes, parsed.to_bytes(), "re-serialization is not stable");
+}
+
+/// Parse invariant: arbitrary bytes parse to an error or a stable fingerprint
+///
+/// Never panics. A successful parse must re-serialize and re-parse identically;
+/// comparing the result with itself must stay within the 0–100 range.
+pub fn parse(data: &[u8]) {
+    match FuzzyFingerprint::from_bytes(data) {
+        Ok(fp) => {
+            let score = compare_hashes(&fp, &fp, 0.3);
+            assert!(score <= 100);
+            // A parsed fingerprint must round-trip.
+            let reparsed = FuzzyFingerprint::from_bytes(&fp.to_bytes())
+                .expect("re-parsing own output must succeed");
+            assert_eq!(fp, reparsed);
+        }
+        Err(_) => { /* rejecting malformed input is the expected outcome */ }
+    }
+}
+
+/// Compare invariant: comparing any two parsed buffers yields a 0–100 score
+///
+/// Splits the input in half to derive two candidate buffers, mirroring an
+/// `Arbitrary`-derived `(Vec<u8>, Vec<u8>)` fuzz input. Never panics.
+pub fn compare_split(data: &[u8]) {
+    let (a, b) = data.split_at(data.len() / 2);
+    if let (Ok(fp_a), Ok(fp_b)) = (
+        FuzzyFingerprint::from_bytes(a),
+        FuzzyFingerprint::from_bytes(b),
+    ) {
+        let score = compare_hashes(&fp_a, &fp_b, 0.3);
+        assert!(score <= 100);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hf_compare, hf_hash};
+
+    /// Simple deterministic byte generator for the smoke corpus
+    fn pseudo_random(seed: u64, len: usize) -> Vec<u8> {
+        let mut s = seed;
+        (0..len)
+            .map(|_| {
+                s = s
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (s >> 33) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn smoke_roundtrip_random() {
+        for seed in 0..16u64 {
+            let data = pseudo_random(seed, (seed as usize + 1) * 97);
+            roundtrip(&data);
+        }
+        roundtrip(b"");
+    }
+
+    #[test]
+    fn smoke_parse_malformed() {
+        // Random noise, truncated fingerprints, and edge lengths must not panic.
+        for seed in 0..32u64 {
+            parse(&pseudo_random(seed, seed as usize));
+        }
+
+        // A real fingerprint truncated at every length.
+        let fp = generate_hash(b"truncation corpus seed data", &HashConfig::default()).unwrap();
+        let bytes = fp.to_bytes();
+        for cut in 0..bytes.len() {
+            parse(&bytes[..cut]);
+        }
+    }
+
+    #[test]
+    fn smoke_compare_malformed() {
+        for seed in 0..32u64 {
+            compare_split(&pseudo_random(seed, (seed as usize) * 7));
+        }
+    }
+
+    #[test]
+    fn smoke_ffi_edge_cases() {
+        // Null / zero-length inputs must be rejected, not dereferenced.
+        let null = std::ptr::null::<u8>();
+        let res = hf_hash(null, 0, std::ptr::null());
+        assert!(res.buffer.is_null());
+
+        let data = b"some data";
+        let res = hf_hash(data.as_ptr(), 0, std::ptr::null());
+        assert!(res.buffer.is_null());
+
+        // hf_compare over null/garbage returns a clamped 0, never crashes.
+        assert_eq!(hf_compare(null, 0, null, 0), 0);
+        let garbage = pseudo_random(1, 64);
+        let score = hf_compare(garbage.as_ptr(), garbage.len(), garbage.as_ptr(), garbage.len());
+        assert!(score <= 100);
+    }
+}
@@ -0,0 +1,570 @@
+//! Searchable fingerprint index with banded-LSH candidate pruning
+//!
+//! Scanning a corpus with pairwise [`compare_hashes`](crate::compare_hashes) is
+//! O(N): every probe runs a full Jaccard over 8192 bloom bits plus a
+//! Levenshtein over the structural vector. [`FingerprintIndex`] makes the common
+//! "find everything similar to this" query sub-linear with banded locality
+//! sensitive hashing: the 128-word content Bloom filter is split into `B` bands,
+//! each band's words are hashed into a bucket keyed by `(band_id, band_hash)`,
+//! and near-identical fingerprints land in the same bucket for at least one
+//! band. A query only runs the exact [`FuzzyFingerprint::similarity`] against the
+//! union of its own bands' buckets instead of the whole corpus.
+
+use crate::model::{FingerprintError, FuzzyFingerprint};
+
+/// Number of 64-bit words in the content Bloom filter of a fingerprint
+const CONTENT_WORDS: usize = 128;
+
+/// Default number of LSH bands over the content filter
+///
+/// 16 bands of 8 words each balances bucket selectivity against recall for the
+/// default 8192-bit filter.
+pub const DEFAULT_NUM_BANDS: usize = 16;
+
+/// Magic prefix for a serialized index ("LXI" + format version 1)
+const INDEX_MAGIC: &[u8; 4] = b"LXI1";
+
+/// Hash a band of content words into a bucket key
+///
+/// FxHash-style mixing (xor-rotate-multiply) seeded by the band id so identical
+/// word runs in different bands never share a key.
+#[inline]
+fn hash_band(words: &[u64], band_id: usize) -> u64 {
+    const K: u64 = 0x517cc1b727220a95;
+    let mut hash = 0xcbf29ce484222325u64 ^ (band_id as u64).wrapping_mul(K);
+    for &word in words {
+        hash = (hash ^ word).rotate_left(5).wrapping_mul(K);
+    }
+    hash
+}
+
+/// Compact open-addressed table mapping band keys to bucket member lists
+///
+/// Linear-probed in the style of odht's SwissTable layout: parallel `keys` /
+/// `occupied` / `bucket_of` arrays sized to a power of two, with the member
+/// id lists held in a side `buckets` vector referenced by index. Grows at a
+/// 0.7 load factor.
+#[derive(Default, Debug, PartialEq)]
+struct BandTable {
+    keys: Vec<u64>,
+    occupied: Vec<bool>,
+    bucket_of: Vec<usize>,
+    buckets: Vec<Vec<usize>>,
+    len: usize,
+    mask: usize,
+}
+
+impl BandTable {
+    fn with_capacity(cap_hint: usize) -> Self {
+        let cap = cap_hint.next_power_of_two().max(16);
+        Self {
+            keys: vec![0u64; cap],
+            occupied: vec![false; cap],
+            bucket_of: vec![0usize; cap],
+            buckets: Vec::new(),
+            len: 0,
+            mask: cap - 1,
+        }
+    }
+
+    #[inline]
+    fn cap(&self) -> usize {
+        self.mask + 1
+    }
+
+    /// Append `id` to the bucket for `key`, creating the bucket on first sight
+    fn add(&mut self, key: u64, id: usize) {
+        if (self.len + 1) * 10 >= self.cap() * 7 {
+            self.grow();
+        }
+
+        let mut i = (key as usize) & self.mask;
+        loop {
+            if !self.occupied[i] {
+                self.occupied[i] = true;
+                self.keys[i] = key;
+                self.bucket_of[i] = self.buckets.len();
+                self.buckets.push(vec![id]);
+                self.len += 1;
+                return;
+            }
+            if self.keys[i] == key {
+                self.buckets[self.bucket_of[i]].push(id);
+                return;
+            }
+            i = (i + 1) & self.mask;
+        }
+    }
+
+    /// Return the bucket members for `key`, if the key is present
+    fn get(&self, key: u64) -> Option<&[usize]> {
+        let mut i = (key as usize) & self.mask;
+        loop {
+            if !self.occupied[i] {
+                return None;
+            }
+            if self.keys[i] == key {
+                return Some(&self.buckets[self.bucket_of[i]]);
+            }
+            i = (i + 1) & self.mask;
+        }
+    }
+
+    /// Double the slot array and re-probe every occupied key (buckets are kept)
+    fn grow(&mut self) {
+        let new_cap = self.cap() * 2;
+        let mut keys = vec![0u64; new_cap];
+        let mut occupied = vec![false; new_cap];
+        let mut bucket_of = vec![0usize; new_cap];
+        let mask = new_cap - 1;
+
+        for i in 0..self.cap() {
+            if !self.occupied[i] {
+                continue;
+            }
+            let key = self.keys[i];
+            let mut j = (key as usize) & mask;
+            while occupied[j] {
+                j = (j + 1) & mask;
+            }
+            occupied[j] = true;
+            keys[j] = key;
+            bucket_of[j] = self.bucket_of[i];
+        }
+
+        self.keys = keys;
+        self.occupied = occupied;
+        self.bucket_of = bucket_of;
+        self.mask = mask;
+    }
+}
+
+/// An index over many fingerprints answering similarity queries sub-linearly
+#[derive(Debug, PartialEq)]
+pub struct FingerprintIndex {
+    num_bands: usize,
+    fingerprints: Vec<FuzzyFingerprint>,
+    table: BandTable,
+}
+
+impl FingerprintIndex {
+    /// Create an empty index with the default band count
+    pub fn new() -> Self {
+        Self::with_bands(DEFAULT_NUM_BANDS)
+    }
+
+    /// Create an empty index with a tunable number of bands (1..=128)
+    ///
+    /// More bands raise recall (a smaller per-band signature collides more
+    /// readily) at the cost of larger candidate sets; fewer bands prune harder.
+    /// Each band is `ceil(128 / num_bands)` words wide, so a count that does not
+    /// divide 128 can yield fewer bands than requested (e.g. 100 gives 64).
+    pub fn with_bands(num_bands: usize) -> Self {
+        let num_bands = num_bands.clamp(1, CONTENT_WORDS);
+        Self {
+            num_bands,
+            fingerprints: Vec::new(),
+            table: BandTable::with_capacity(16),
+        }
+    }
+
+    /// Number of fingerprints stored
+    pub fn len(&self) -> usize {
+        self.fingerprints.len()
+    }
+
+    /// Whether the index holds no fingerprints
+    pub fn is_empty(&self) -> bool {
+        self.fingerprints.is_empty()
+    }
+
+    /// Band key for each band of a fingerprint's content filter
+    fn band_keys(&self, fp: &FuzzyFingerprint) -> Vec<u64> {
+        let band_size = CONTENT_WORDS.div_ceil(self.num_bands);
+        fp.content_hash
+            .chunks(band_size)
+            .enumerate()
+            .map(|(band_id, words)| hash_band(words, band_id))
+            .collect()
+    }
+
+    /// Insert a fingerprint, returning the id assigned to it
+    pub fn insert(&mut self, fp: FuzzyFingerprint) -> usize {
+        let id = self.fingerprints.len();
+        let keys = self.band_keys(&fp);
+        for key in keys {
+            self.table.add(key, id);
+        }
+        self.fingerprints.push(fp);
+        id
+    }
+
+    /// Retrieve a stored fingerprint by id
+    pub fn get(&self, id: usize) -> Option<&FuzzyFingerprint> {
+        self.fingerprints.get(id)
+    }
+
+    /// Collect the candidate ids whose bands collide with the probe's
+    fn candidates(&self, fp: &FuzzyFingerprint) -> Vec<usize> {
+        let mut seen = vec![false; self.fingerprints.len()];
+        let mut candidates = Vec::new();
+        for key in self.band_keys(fp) {
+            if let Some(ids) = self.table.get(key) {
+                for &id in ids {
+                    if !seen[id] {
+                        seen[id] = true;
+                        candidates.push(id);
+                    }
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Find every stored fingerprint with similarity `>= threshold`
+    ///
+    /// Only the LSH candidate set is scored exactly, so this runs in time
+    /// proportional to the number of colliding fingerprints rather than the
+    /// whole corpus. Results are `(id, score)` pairs sorted by descending score.
+    pub fn query(&self, fp: &FuzzyFingerprint, threshold: u8, alpha: f32) -> Vec<(usize, u8)> {
+        let mut matches: Vec<(usize, u8)> = self
+            .candidates(fp)
+            .into_iter()
+            .filter_map(|id| {
+                let score = self.fingerprints[id].similarity(fp, alpha);
+                (score >= threshold).then_some((id, score))
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.1.cmp(&a.1));
+        matches
+    }
+
+    /// Serialize the index to a byte buffer for on-disk storage
+    ///
+    /// The band table is a deterministic function of the stored fingerprints and
+    /// the band count, so only those are persisted; [`from_bytes`](Self::from_bytes)
+    /// rebuilds the table on load. This keeps the file compact and lets an index
+    /// be built once and reloaded (e.g. via a memory-mapped buffer passed as the
+    /// input slice).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(INDEX_MAGIC);
+        bytes.extend_from_slice(&(self.num_bands as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.fingerprints.len() as u32).to_le_bytes());
+        for fp in &self.fingerprints {
+            let fp_bytes = fp.to_bytes();
+            bytes.extend_from_slice(&(fp_bytes.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&fp_bytes);
+        }
+        bytes
+    }
+
+    /// Reconstruct an index from a buffer produced by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FingerprintError> {
+        if bytes.len() < 12 || &bytes[0..4] != INDEX_MAGIC {
+            return Err(FingerprintError::InvalidMagic);
+        }
+
+        let num_bands = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+        let count = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize;
+
+        let mut index = Self::with_bands(num_bands);
+        let mut offset = 12;
+        for _ in 0..count {
+            if offset + 4 > bytes.len() {
+                return Err(FingerprintError::InvalidSize);
+            }
+            let len = u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ]) as usize;
+            offset += 4;
+
+            if offset + len > bytes.len() {
+                return Err(FingerprintError::InvalidSize);
+            }
+            let fp = FuzzyFingerprint::from_bytes(&bytes[offset..offset + len])?;
+            offset += len;
+            index.insert(fp);
+        }
+
+        Ok(index)
+    }
+}
+
+impl Default for FingerprintIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Total number of addressable bits in the content Bloom filter
+const CONTENT_BITS: usize = CONTENT_WORDS * 64;
+
+/// Default bit-sampling parameters: 8 bands of 12 sampled bits each
+pub const DEFAULT_BANDS: usize = 8;
+pub const DEFAULT_ROWS: usize = 12;
+
+/// Fixed seed so sampled bit positions are reproducible across processes
+const SAMPLE_SEED: u64 = 0x5a1a_d0c7_1b3e_9f2d;
+
+/// splitmix64 step — deterministic position generator for bit sampling
+#[inline]
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Bit-sampling LSH index for fast top-k similarity retrieval
+///
+/// Sibling to [`FingerprintIndex`]: where that prunes by hashing whole bloom
+/// words per band, this samples `r` fixed (deterministically seeded) bit
+/// positions per band, forming an `r`-bit signature. Two filters that agree on a
+/// band's sampled bits collide, and the probability of collision rises with
+/// their bit-level Jaccard, so near-identical files share at least one bucket.
+/// A [`query_topk`](BitSampleIndex::query_topk) unions the colliding candidate
+/// ids and scores only those exactly. Tuning `(b, r)` trades recall for speed:
+/// more bands or fewer rows widen the candidate set.
+pub struct BitSampleIndex {
+    bands: usize,
+    rows: usize,
+    /// `bands * rows` sampled bit positions in `0..CONTENT_BITS`
+    positions: Vec<usize>,
+    /// Stored fingerprints keyed by caller-supplied id
+    fingerprints: std::collections::HashMap<u64, FuzzyFingerprint>,
+    /// Per-band map from band signature to the ids that produced it
+    buckets: Vec<std::collections::HashMap<u64, Vec<u64>>>,
+}
+
+impl BitSampleIndex {
+    /// Create an index with the default band/row parameters
+    pub fn new() -> Self {
+        Self::with_params(DEFAULT_BANDS, DEFAULT_ROWS)
+    }
+
+    /// Create an index sampling `bands` bands of `rows` bits each (1..=64 rows)
+    pub fn with_params(bands: usize, rows: usize) -> Self {
+        let bands = bands.max(1);
+        let rows = rows.clamp(1, 64);
+
+        // Deterministically draw all sampled positions so every process (and a
+        // reloaded index) samples the same bits.
+        let mut state = SAMPLE_SEED;
+        let positions = (0..bands * rows)
+            .map(|_| (splitmix64(&mut state) as usize) % CONTENT_BITS)
+            .collect();
+
+        Self {
+            bands,
+            rows,
+            positions,
+            fingerprints: std::collections::HashMap::new(),
+            buckets: vec![std::collections::HashMap::new(); bands],
+        }
+    }
+
+    /// Number of stored fingerprints
+    pub fn len(&self) -> usize {
+        self.fingerprints.len()
+    }
+
+    /// Whether the index holds no fingerprints
+    pub fn is_empty(&self) -> bool {
+        self.fingerprints.is_empty()
+    }
+
+    /// Read a single content bit of a fingerprint
+    #[inline]
+    fn bit(fp: &FuzzyFingerprint, pos: usize) -> u64 {
+        (fp.content_hash[pos / 64] >> (pos % 64)) & 1
+    }
+
+    /// Compute the `bands` band signatures of a fingerprint
+    fn signatures(&self, fp: &FuzzyFingerprint) -> Vec<u64> {
+        (0..self.bands)
+            .map(|band| {
+                let base = band * self.rows;
+                let mut sig = 0u64;
+                for row in 0..self.rows {
+                    sig = (sig << 1) | Self::bit(fp, self.positions[base + row]);
+                }
+                sig
+            })
+            .collect()
+    }
+
+    /// Insert a fingerprint under a caller-supplied id
+    ///
+    /// Re-inserting an existing id replaces the stored fingerprint but leaves the
+    /// old bucket entries in place; callers that update in place should use fresh
+    /// ids.
+    pub fn insert(&mut self, id: u64, fp: &FuzzyFingerprint) {
+        for (band, sig) in self.signatures(fp).into_iter().enumerate() {
+            self.buckets[band].entry(sig).or_default().push(id);
+        }
+        self.fingerprints.insert(id, fp.clone());
+    }
+
+    /// Return up to `k` stored fingerprints most similar to `fp`
+    ///
+    /// Only ids that collide with the probe in at least one band are scored with
+    /// the exact [`FuzzyFingerprint::similarity`]; results are `(id, score)`
+    /// sorted by descending score and truncated to `k`.
+    pub fn query_topk(&self, fp: &FuzzyFingerprint, k: usize, alpha: f32) -> Vec<(u64, u8)> {
+        let mut seen = std::collections::HashSet::new();
+        for (band, sig) in self.signatures(fp).into_iter().enumerate() {
+            if let Some(ids) = self.buckets[band].get(&sig) {
+                for &id in ids {
+                    seen.insert(id);
+                }
+            }
+        }
+
+        let mut scored: Vec<(u64, u8)> = seen
+            .into_iter()
+            .map(|id| (id, self.fingerprints[&id].similarity(fp, alpha)))
+            .collect();
+
+        scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        scored.truncate(k);
+        scored
+    }
+}
+
+impl Default for BitSampleIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_hash, HashConfig};
+
+    fn hash(data: &[u8]) -> FuzzyFingerprint {
+        let mut config = HashConfig::default();
+        config.enable_parallel = false;
+        config.min_modulus = 64;
+        generate_hash(data, &config).unwrap()
+    }
+
+    #[test]
+    fn test_index_finds_self() {
+        let mut data = Vec::new();
+        for _ in 0..30 {
+            data.extend_from_slice(b"Indexable content for the fingerprint index test. ");
+        }
+
+        let mut index = FingerprintIndex::new();
+        let fp = hash(&data);
+        let id = index.insert(fp.clone());
+
+        let results = index.query(&fp, 90, 0.3);
+        assert!(results.iter().any(|&(rid, score)| rid == id && score == 100));
+    }
+
+    #[test]
+    fn test_index_candidate_pruning() {
+        let mut index = FingerprintIndex::new();
+
+        // Two near-identical documents and one unrelated one.
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        for _ in 0..30 {
+            a.extend_from_slice(b"The quick brown fox jumps over the lazy dog. ");
+            b.extend_from_slice(b"The quick brown fox leaps over the lazy dog. ");
+        }
+        let c: Vec<u8> = (0..2000).map(|i| ((i * 131 + 7) % 256) as u8).collect();
+
+        let id_a = index.insert(hash(&a));
+        let _id_b = index.insert(hash(&b));
+        let _id_c = index.insert(hash(&c));
+
+        // Probing with A should surface A (and likely B) but score C low/absent.
+        let probe = hash(&a);
+        let results = index.query(&probe, 20, 0.3);
+        assert!(results.iter().any(|&(rid, _)| rid == id_a));
+    }
+
+    #[test]
+    fn test_index_serialization_roundtrip() {
+        let mut index = FingerprintIndex::with_bands(8);
+        let mut data = Vec::new();
+        for _ in 0..20 {
+            data.extend_from_slice(b"Serialization roundtrip content block. ");
+        }
+        let fp = hash(&data);
+        index.insert(fp.clone());
+
+        let bytes = index.to_bytes();
+        let restored = FingerprintIndex::from_bytes(&bytes).unwrap();
+
+        assert_eq!(restored.len(), index.len());
+        let results = restored.query(&fp, 90, 0.3);
+        assert!(results.iter().any(|&(_, score)| score == 100));
+    }
+
+    #[test]
+    fn test_bitsample_near_identical_collide() {
+        let mut index = BitSampleIndex::new();
+
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        for _ in 0..40 {
+            a.extend_from_slice(b"Bit-sampling collision test with shared content here. ");
+            b.extend_from_slice(b"Bit-sampling collision test with shared content here! ");
+        }
+
+        index.insert(1, &hash(&a));
+        index.insert(2, &hash(&b));
+
+        // Probing with A must surface A (exact), and the near-identical B should
+        // collide into at least one shared band bucket.
+        let probe = hash(&a);
+        let results = index.query_topk(&probe, 5, 0.3);
+        assert!(results.iter().any(|&(id, score)| id == 1 && score == 100));
+        assert!(results.iter().any(|&(id, _)| id == 2));
+    }
+
+    #[test]
+    fn test_bitsample_topk_limit() {
+        let mut index = BitSampleIndex::with_params(12, 8);
+        for id in 0..6u64 {
+            let mut data = Vec::new();
+            for _ in 0..20 {
+                data.extend_from_slice(b"Shared prefix so candidates collide across bands. ");
+            }
+            data.extend_from_slice(format!("unique-tail-{}", id).as_bytes());
+            index.insert(id, &hash(&data));
+        }
+
+        let probe = {
+            let mut data = Vec::new();
+            for _ in 0..20 {
+                data.extend_from_slice(b"Shared prefix so candidates collide across bands. ");
+            }
+            hash(&data)
+        };
+
+        let results = index.query_topk(&probe, 3, 0.3);
+        assert!(results.len() <= 3);
+        // Scores must be in non-increasing order.
+        assert!(results.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn test_index_from_bytes_bad_magic() {
+        let bad = vec![0u8; 16];
+        assert_eq!(
+            FingerprintIndex::from_bytes(&bad),
+            Err(FingerprintError::InvalidMagic)
+        );
+    }
+}
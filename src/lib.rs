@@ -6,6 +6,8 @@
 #![allow(clippy::missing_safety_doc)]
 
 pub mod algo;
+pub mod fuzz;
+pub mod index;
 pub mod model;
 pub mod utils;
 
@@ -24,6 +26,21 @@ const PARALLEL_THRESHOLD: usize = 1_048_576;
 /// Î± = 0.3 gives 30% weight to structure, 70% to content
 const DEFAULT_ALPHA: f32 = 0.3;
 
+/// Feature extraction strategy for the content Bloom filter
+///
+/// [`Cdc`](FeatureMode::Cdc) selects content-defined chunk windows via the
+/// BuzHash trigger; [`Repeat`](FeatureMode::Repeat) instead emits features for
+/// long internal back-references (LZ77-style longest matches), making the
+/// fingerprint sensitive to block-level duplication.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeatureMode {
+    /// Content-defined chunking (default)
+    Cdc = 0,
+    /// Longest-match repeat detection
+    Repeat = 1,
+}
+
 /// Configuration for fuzzy hashing
 #[repr(C)]
 pub struct HashConfig {
@@ -36,6 +53,30 @@ pub struct HashConfig {
 
     /// Minimum trigger modulus (affects feature density)
     pub min_modulus: u64,
+
+    /// Target false-positive rate for the content Bloom filter
+    ///
+    /// The serialized fingerprint format only has a fixed 8192-bit content
+    /// slot, so an auto-sized filter would have its `m`/`k` silently
+    /// truncated on the way out. Until a variable-width content section
+    /// exists, [`generate_hash`] rejects any value greater than 0 with
+    /// [`FingerprintError::UnsupportedConfig`]; keep this at 0 to use the
+    /// fixed 8192-bit default layout.
+    pub target_fpr: f64,
+
+    /// Use the cross-entropy structural descriptor instead of Shannon entropy
+    ///
+    /// Cross-entropy against a reference byte distribution distinguishes data
+    /// that merely shares a histogram shape (text vs base64 vs ciphertext).
+    /// Fingerprints built in this mode are flagged and only compare against
+    /// other cross-entropy fingerprints.
+    pub use_cross_entropy: bool,
+
+    /// Feature extraction strategy for the content filter
+    ///
+    /// Defaults to [`FeatureMode::Cdc`]. [`FeatureMode::Repeat`] swaps the
+    /// CDC window features for LZ77 longest-match features.
+    pub feature_mode: FeatureMode,
 }
 
 impl Default for HashConfig {
@@ -44,6 +85,9 @@ impl Default for HashConfig {
             enable_parallel: true,
             alpha: DEFAULT_ALPHA,
             min_modulus: 16,  // OPTIMAL: High sensitivity for small files, adaptive scaling prevents saturation on large files
+            target_fpr: 0.0,  // 0 = fixed 8192-bit layout; >0 is rejected by generate_hash
+            use_cross_entropy: false,  // Shannon entropy by default
+            feature_mode: FeatureMode::Cdc,  // content-defined chunking by default
         }
     }
 }
@@ -59,104 +103,189 @@ pub fn generate_hash(data: &[u8], config: &HashConfig) -> Result<FuzzyFingerprin
         return Err(FingerprintError::InvalidSize);
     }
 
-    // Phase I: Normalization happens on-the-fly in Phase II and III
-    // (Iterator-based, no allocation)
+    // The serialized fingerprint still carries a fixed 8192-bit content slot, so
+    // an auto-sized filter would have its `m`/`k` silently truncated on the way
+    // out. Reject `target_fpr > 0` until the variable-width content section lands
+    // rather than emit a content hash that does not round-trip.
+    if config.target_fpr > 0.0 {
+        return Err(FingerprintError::UnsupportedConfig);
+    }
 
-    // Phase II: Generate structural vector (entropy-based)
-    let structural_data = generate_structural_vector(data);
+    // The longest-match mode scans the whole normalized stream for back-
+    // references and does not use the CDC trigger, so it takes a dedicated path.
+    if config.feature_mode == FeatureMode::Repeat {
+        let (structural_data, flags) = if config.use_cross_entropy {
+            let ref_log = algo::reference_log_table(&algo::default_reference_distribution());
+            (
+                algo::generate_structural_vector_cross(data, &ref_log),
+                model::FLAG_CROSS_ENTROPY,
+            )
+        } else {
+            (generate_structural_vector(data), 0)
+        };
 
-    // Phase III: Generate content hash (BuzHash + Bloom Filter)
-    let content_bloom = if config.enable_parallel && data.len() > PARALLEL_THRESHOLD {
-        generate_content_hash_parallel(data, config)
-    } else {
-        generate_content_hash_sequential(data, config)
-    };
+        let content_bloom = generate_content_hash_repeat(data, config);
+        return Ok(FuzzyFingerprint::new_with_flags(content_bloom, structural_data, flags));
+    }
+
+    // Large inputs keep the parallel content path (and its companion parallel
+    // structural scan); everything else flows through the streaming hasher so
+    // the one-shot and incremental APIs produce identical fingerprints.
+    if config.enable_parallel && data.len() > PARALLEL_THRESHOLD {
+        let (structural_data, flags) = if config.use_cross_entropy {
+            let ref_log = algo::reference_log_table(&algo::default_reference_distribution());
+            (
+                algo::generate_structural_vector_cross(data, &ref_log),
+                model::FLAG_CROSS_ENTROPY,
+            )
+        } else {
+            (generate_structural_vector(data), 0)
+        };
+
+        let content_bloom = generate_content_hash_parallel(data, config);
+        return Ok(FuzzyFingerprint::new_with_flags(content_bloom, structural_data, flags));
+    }
 
-    Ok(FuzzyFingerprint::new(content_bloom, structural_data))
+    let mut hasher = LavinHasher::new(config, Some(data.len()))?;
+    hasher.update(data);
+    Ok(hasher.finalize())
 }
 
-/// Generate content hash sequentially (for small files)
-fn generate_content_hash_sequential(data: &[u8], config: &HashConfig) -> BloomFilter {
-    // ADAPTIVE MODULUS: Scale with file size to prevent Bloom saturation
-    // Target: ~1200 features for optimal Bloom filter usage (50% fill rate)
-    // with m=8,192 bits and k=5.
-    let target_features = 1200;
+/// Overlap prefix carried into each parallel chunk (one less than the 64-byte
+/// BuzHash window, the minimum needed to warm the rolling state)
+const CHUNK_OVERLAP: usize = 63;
 
-    // Calculated modulus ensures we extract roughly `target_features` items
-    let calculated_modulus = if data.len() > target_features * config.min_modulus as usize {
-        (data.len() / target_features).max(config.min_modulus as usize) as u64
-    } else {
-        config.min_modulus
-    };
+/// Minimum back-reference length worth recording as a repeat feature
+const MIN_MATCH: usize = 4;
 
-    let modulus = calculated_modulus;
+/// Maximum back-reference length (LZ77/deflate convention)
+const MAX_MATCH: usize = 258;
 
-    // DEBUG: Log modulus calculation
-    eprintln!("DEBUG: file_size={}, target_features={}, min_modulus={}, calculated_modulus={}",
-              data.len(), target_features, config.min_modulus, modulus);
-    let mut buzhash = BuzHash::new();
-    let mut builder = BloomFilterBuilder::new();
+/// Size of the chained hash head table (power of two)
+const REPEAT_HASH_SIZE: usize = 1 << 15;
 
-    let mut window_data = Vec::with_capacity(64);
-    #[cfg(test)]
-    let mut trigger_count = 0;
+/// Longest back-reference chain walked per position (bounds worst-case cost)
+const MAX_CHAIN: usize = 128;
 
-    for (i, &byte) in data.iter().enumerate() {
-        // Phase I: Normalization (on-the-fly)
-        let normalized_byte = normalize_byte(byte);
+/// Hash of a 3-byte context into the chained hash table
+#[inline]
+fn repeat_hash3(a: u8, b: u8, c: u8) -> usize {
+    (((a as usize) << 10) ^ ((b as usize) << 5) ^ (c as usize)) & (REPEAT_HASH_SIZE - 1)
+}
 
-        // Update rolling hash
-        buzhash.update(normalized_byte);
+/// Generate content hash from LZ77-style longest back-reference matches
+///
+/// Borrows deflate's chained-hash longest-match search: a 3-byte hash of the
+/// normalized stream indexes a chain of recent positions, and at each step the
+/// longest greedy match (capped at [`MAX_MATCH`]) is found by walking up to
+/// [`MAX_CHAIN`] earlier candidates. Each sufficiently long repeat emits a Bloom
+/// feature keyed by `(match_length, context_hash)`, so two files that share a
+/// duplicated block land many identical features regardless of their overall
+/// entropy.
+fn generate_content_hash_repeat(data: &[u8], config: &HashConfig) -> BloomFilter {
+    let norm: Vec<u8> = data.iter().map(|&b| normalize_byte(b)).collect();
+    let n = norm.len();
+
+    let modulus = adaptive_modulus(n, config.min_modulus);
+    let mut builder = BloomFilterBuilder::new();
 
-        // Track window for feature extraction
-        window_data.push(normalized_byte);
-        if window_data.len() > 64 {
-            window_data.remove(0);
+    const NIL: usize = usize::MAX;
+    let mut head = vec![NIL; REPEAT_HASH_SIZE];
+    let mut prev = vec![NIL; n];
+
+    let mut i = 0;
+    while i + MIN_MATCH <= n {
+        let h = repeat_hash3(norm[i], norm[i + 1], norm[i + 2]);
+
+        // Walk the chain of earlier positions with the same 3-byte context,
+        // tracking the longest match.
+        let mut best_len = 0usize;
+        let mut candidate = head[h];
+        let mut depth = 0;
+        let max_here = (n - i).min(MAX_MATCH);
+        while candidate != NIL && depth < MAX_CHAIN {
+            let mut len = 0;
+            while len < max_here && norm[candidate + len] == norm[i + len] {
+                len += 1;
+            }
+            if len > best_len {
+                best_len = len;
+            }
+            candidate = prev[candidate];
+            depth += 1;
         }
 
-        // Check if this is a trigger point
-        if i >= 64 && buzhash.is_trigger(modulus) {
-            // Add feature to Bloom filter
-            builder.add_feature(&window_data);
-            #[cfg(test)]
-            {
-                trigger_count += 1;
+        // Link this position into the chain before advancing.
+        prev[i] = head[h];
+        head[h] = i;
+
+        if best_len >= MIN_MATCH {
+            // Feature: (match length, context hash) of the repeat.
+            let mut feature = Vec::with_capacity(8);
+            feature.extend_from_slice(&(best_len as u32).to_le_bytes());
+            feature.extend_from_slice(&(h as u32).to_le_bytes());
+            builder.add_feature(&feature);
+
+            // Greedy: skip the matched span, but keep chain links dense by
+            // inserting the positions we pass over.
+            for j in (i + 1)..(i + best_len).min(n - MIN_MATCH + 1) {
+                let hj = repeat_hash3(norm[j], norm[j + 1], norm[j + 2]);
+                prev[j] = head[hj];
+                head[hj] = j;
             }
+            i += best_len;
+        } else {
+            i += 1;
         }
     }
 
-    #[cfg(test)]
-    eprintln!("Modulus: {}, Triggers detected: {}, Data length: {}", modulus, trigger_count, data.len());
-
     builder.build()
 }
 
-/// Generate content hash in parallel (for large files)
-fn generate_content_hash_parallel(data: &[u8], config: &HashConfig) -> BloomFilter {
-    let chunk_size = cmp::max(PARALLEL_THRESHOLD / 4, 256 * 1024); // 256KB min chunks
-
-    // ADAPTIVE MODULUS: Scale with file size to prevent Bloom saturation
+/// Adaptive trigger modulus as a function of the full input size
+///
+/// Scales with file size so the feature count stays near the Bloom filter's
+/// optimal fill regardless of input length.
+#[inline]
+fn adaptive_modulus(total_len: usize, min_modulus: u64) -> u64 {
+    // Target ~1200 features for a half-full 8192-bit / k=5 filter.
     let target_features = 1200;
-    let calculated_modulus = if data.len() > target_features * config.min_modulus as usize {
-        (data.len() / target_features).max(config.min_modulus as usize) as u64
+    if total_len > target_features * min_modulus as usize {
+        (total_len / target_features).max(min_modulus as usize) as u64
     } else {
-        config.min_modulus
-    };
-    let modulus = calculated_modulus;
+        min_modulus
+    }
+}
 
-    // DEBUG: Log modulus calculation
-    eprintln!("DEBUG PARALLEL: file_size={}, target_features={}, min_modulus={}, calculated_modulus={}",
-              data.len(), target_features, config.min_modulus, modulus);
+/// Generate content hash in parallel (for large files)
+///
+/// Produces a filter bit-for-bit identical to the sequential path. Each chunk
+/// after the first is prefixed with the last [`CHUNK_OVERLAP`] bytes of the
+/// previous chunk: that prefix warms the rolling [`BuzHash`] so its state at the
+/// chunk's first real byte matches the serial scan, but features are only
+/// emitted once the consumed position passes the overlap, so every global window
+/// is produced exactly once and by exactly one chunk. The partial filters are
+/// OR-merged, reproducing the serial Bloom filter.
+fn generate_content_hash_parallel(data: &[u8], config: &HashConfig) -> BloomFilter {
+    let chunk_size = cmp::max(PARALLEL_THRESHOLD / 4, 256 * 1024); // 256KB min chunks
+    let modulus = adaptive_modulus(data.len(), config.min_modulus);
+    let num_chunks = data.len().div_ceil(chunk_size);
+
+    // Process chunks in parallel, each warmed by the previous chunk's tail.
+    let partial_blooms: Vec<BloomFilter> = (0..num_chunks)
+        .into_par_iter()
+        .map(|c| {
+            let start = c * chunk_size;
+            let end = cmp::min(start + chunk_size, data.len());
+            // First chunk has no predecessor; others carry a 63-byte prefix.
+            let overlap = cmp::min(CHUNK_OVERLAP, start);
+            let slice = &data[start - overlap..end];
 
-    // Process chunks in parallel
-    let partial_blooms: Vec<BloomFilter> = data
-        .par_chunks(chunk_size)
-        .map(|chunk| {
             let mut buzhash = BuzHash::new();
             let mut builder = BloomFilterBuilder::new();
             let mut window_data = Vec::with_capacity(64);
 
-            for (i, &byte) in chunk.iter().enumerate() {
+            for (j, &byte) in slice.iter().enumerate() {
                 let normalized_byte = normalize_byte(byte);
                 buzhash.update(normalized_byte);
 
@@ -165,7 +294,11 @@ fn generate_content_hash_parallel(data: &[u8], config: &HashConfig) -> BloomFilt
                     window_data.remove(0);
                 }
 
-                if i >= 64 && buzhash.is_trigger(modulus) {
+                // Global position of this byte in the full input.
+                let global = start - overlap + j;
+                // Emit only past the warm-up prefix (so this chunk owns the
+                // window) and past the serial `i >= 64` trigger guard.
+                if j >= overlap && global >= 64 && buzhash.is_trigger(modulus) {
                     builder.add_feature(&window_data);
                 }
             }
@@ -175,7 +308,7 @@ fn generate_content_hash_parallel(data: &[u8], config: &HashConfig) -> BloomFilt
         .collect();
 
     // Merge all partial Bloom filters (bitwise OR)
-    let mut final_bloom = BloomFilter::new();
+    let mut final_bloom = BloomFilterBuilder::new().build();
     for partial in partial_blooms {
         final_bloom.merge(&partial);
     }
@@ -209,6 +342,369 @@ pub fn compare_hashes(hash_a: &FuzzyFingerprint, hash_b: &FuzzyFingerprint, alph
     hash_a.similarity(hash_b, alpha)
 }
 
+/// Compare two fuzzy hashes using the cardinality-corrected content estimator
+///
+/// Same as [`compare_hashes`] but the content term corrects for Bloom filter
+/// saturation, giving more accurate scores for near-full fingerprints.
+pub fn compare_hashes_corrected(
+    hash_a: &FuzzyFingerprint,
+    hash_b: &FuzzyFingerprint,
+    alpha: f32,
+) -> u8 {
+    hash_a.estimated_similarity(hash_b, alpha)
+}
+
+/// Incremental, streaming hasher producing a [`FuzzyFingerprint`]
+///
+/// Digest-style wrapper around the DLAH pipeline: feed arbitrary chunks with
+/// [`update`](LavinHasher::update) as they arrive (from a socket, an mmap read
+/// loop, etc.) and call [`finalize`](LavinHasher::finalize) once. It owns the
+/// rolling [`BuzHash`], the 64-byte content window, and a [`BloomFilterBuilder`];
+/// the structural layer is accumulated through a running per-block byte
+/// histogram that is reduced to one entropy nibble each time a block fills, so
+/// no more than `block_size` of state is retained.
+///
+/// The adaptive trigger modulus needs the total size, which is unknown
+/// mid-stream: pass a `size_hint` to [`new`](LavinHasher::new) to reproduce the
+/// one-shot density, or omit it to fall back to a fixed `config.min_modulus`.
+/// [`generate_hash`] uses this type for CDC-mode inputs that take the
+/// sequential path. The hasher always extracts CDC features; it does not
+/// consult `config.feature_mode`.
+pub struct LavinHasher {
+    buzhash: BuzHash,
+    bloom: BloomFilterBuilder,
+    modulus: u64,
+    /// Up to 64 normalized bytes forming the current content feature window
+    window: Vec<u8>,
+    /// Count of bytes consumed so far (global position for the trigger guard)
+    pos: usize,
+    /// Fixed structural block size
+    block_size: usize,
+    /// Byte histogram for the in-progress structural block
+    block_hist: [u32; 256],
+    /// Bytes accumulated into the current structural block
+    block_fill: usize,
+    /// Quantized entropy nibbles emitted by completed blocks
+    nibbles: Vec<u8>,
+    /// Mode flags recorded on the produced fingerprint
+    flags: u8,
+    /// Reference log table when hashing in cross-entropy mode
+    ref_log: Option<[f32; 256]>,
+}
+
+impl LavinHasher {
+    /// Create a streaming hasher for the given configuration
+    ///
+    /// `size_hint` fixes the structural `block_size` and the content trigger
+    /// `modulus` to the values [`generate_hash`] would pick for an input of that
+    /// size. Without a hint, the minimum block size and `config.min_modulus` are
+    /// used so triggers stay stable regardless of how the stream is chunked.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FingerprintError::UnsupportedConfig`] for `target_fpr > 0`, for
+    /// the same reason [`generate_hash`] rejects it: the serialized fingerprint
+    /// still carries a fixed 8192-bit content slot, so an auto-sized filter
+    /// would not round-trip through [`FuzzyFingerprint::to_bytes`].
+    pub fn new(config: &HashConfig, size_hint: Option<usize>) -> Result<Self, FingerprintError> {
+        use algo::entropy::{MIN_BLOCK_SIZE, TARGET_SIGNATURE_LEN};
+
+        if config.target_fpr > 0.0 {
+            return Err(FingerprintError::UnsupportedConfig);
+        }
+
+        let block_size = match size_hint {
+            Some(n) => cmp::max(MIN_BLOCK_SIZE, n / TARGET_SIGNATURE_LEN),
+            None => MIN_BLOCK_SIZE,
+        };
+
+        let modulus = match size_hint {
+            Some(n) => adaptive_modulus(n, config.min_modulus),
+            None => config.min_modulus,
+        };
+
+        let bloom = BloomFilterBuilder::new();
+
+        let (flags, ref_log) = if config.use_cross_entropy {
+            let table = algo::reference_log_table(&algo::default_reference_distribution());
+            (model::FLAG_CROSS_ENTROPY, Some(table))
+        } else {
+            (0, None)
+        };
+
+        Ok(Self {
+            buzhash: BuzHash::new(),
+            bloom,
+            modulus,
+            window: Vec::with_capacity(64),
+            pos: 0,
+            block_size,
+            block_hist: [0u32; 256],
+            block_fill: 0,
+            nibbles: Vec::new(),
+            flags,
+            ref_log,
+        })
+    }
+
+    /// Reduce the current structural block to a nibble and reset the histogram
+    fn flush_block(&mut self) {
+        let nibble = match &self.ref_log {
+            Some(ref_log) => {
+                let h = algo::entropy::cross_entropy_from_histogram(
+                    &self.block_hist,
+                    self.block_fill,
+                    ref_log,
+                );
+                algo::entropy::quantize_entropy(h)
+            }
+            None => {
+                let h = algo::entropy::entropy_from_histogram(&self.block_hist, self.block_fill);
+                algo::entropy::quantize_entropy(h)
+            }
+        };
+        self.nibbles.push(nibble);
+        self.block_hist = [0u32; 256];
+        self.block_fill = 0;
+    }
+
+    /// Feed the next slice of input into the hasher
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            // Phase III: content hash over the normalized byte stream.
+            let normalized_byte = normalize_byte(byte);
+            self.buzhash.update(normalized_byte);
+
+            self.window.push(normalized_byte);
+            if self.window.len() > 64 {
+                self.window.remove(0);
+            }
+
+            if self.pos >= 64 && self.buzhash.is_trigger(self.modulus) {
+                self.bloom.add_feature(&self.window);
+            }
+
+            // Phase II: structural entropy over the raw byte stream, one running
+            // histogram per block.
+            self.block_hist[byte as usize] += 1;
+            self.block_fill += 1;
+            if self.block_fill == self.block_size {
+                self.flush_block();
+            }
+
+            self.pos += 1;
+        }
+    }
+
+    /// Consume the hasher and produce the final fingerprint
+    pub fn finalize(mut self) -> FuzzyFingerprint {
+        if self.block_fill > 0 {
+            self.flush_block();
+        }
+
+        let struct_data = algo::entropy::pack_nibbles(&self.nibbles);
+        FuzzyFingerprint::new_with_flags(self.bloom.build(), struct_data, self.flags)
+    }
+}
+
+impl std::io::Write for LavinHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Incremental, streaming builder for [`FuzzyFingerprint`]
+///
+/// Feeds data through the DLAH pipeline one [`update`](FingerprintBuilder::update)
+/// call at a time, so callers can fingerprint a file (or socket) as it streams
+/// by instead of buffering it whole. Peak memory is O(`block_size`): only the
+/// current structural block and the 64-byte content window are retained; the
+/// content Bloom filter is additive, so features are inserted as they trigger.
+///
+/// Adaptive block sizing in [`generate_structural_vector`] needs the total
+/// length up front, so [`new`](FingerprintBuilder::new) takes an `expected_len`
+/// hint that fixes `block_size` once. When the hint matches the input exactly,
+/// the result is identical to [`generate_hash`] with the default config.
+///
+/// Implements [`std::io::Write`], so `std::io::copy` from any reader fills it.
+pub struct FingerprintBuilder {
+    buzhash: BuzHash,
+    bloom: BloomFilterBuilder,
+    modulus: u64,
+    /// Up to 64 normalized bytes forming the current content feature window
+    window: Vec<u8>,
+    /// Count of bytes consumed so far (global position for the trigger guard)
+    pos: usize,
+    /// Fixed structural block size derived from the length hint
+    block_size: usize,
+    /// Raw bytes accumulated for the in-progress structural block
+    block: Vec<u8>,
+    /// Quantized entropy nibbles emitted by completed blocks
+    nibbles: Vec<u8>,
+}
+
+impl FingerprintBuilder {
+    /// Create a builder sized for an input of roughly `expected_len` bytes
+    ///
+    /// The hint fixes the structural `block_size` and the trigger `modulus`
+    /// (the same adaptive values [`generate_hash`] would pick for a buffer of
+    /// that size), so the streamed fingerprint matches the one-shot path when
+    /// the hint is accurate.
+    pub fn new(expected_len: usize) -> Self {
+        use algo::entropy::{MIN_BLOCK_SIZE, TARGET_SIGNATURE_LEN};
+
+        let block_size = cmp::max(MIN_BLOCK_SIZE, expected_len / TARGET_SIGNATURE_LEN);
+
+        // Mirror the arithmetic of `adaptive_modulus` with the default minimum,
+        // using the length hint in place of the (unknown) streamed total.
+        let target_features = 1200;
+        let min_modulus = HashConfig::default().min_modulus;
+        let modulus = if expected_len > target_features * min_modulus as usize {
+            (expected_len / target_features).max(min_modulus as usize) as u64
+        } else {
+            min_modulus
+        };
+
+        Self {
+            buzhash: BuzHash::new(),
+            bloom: BloomFilterBuilder::new(),
+            modulus,
+            window: Vec::with_capacity(64),
+            pos: 0,
+            block_size,
+            block: Vec::with_capacity(block_size),
+            nibbles: Vec::new(),
+        }
+    }
+
+    /// Feed the next slice of input into the builder
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            // Phase III: content hash over the normalized byte stream.
+            let normalized_byte = normalize_byte(byte);
+            self.buzhash.update(normalized_byte);
+
+            self.window.push(normalized_byte);
+            if self.window.len() > 64 {
+                self.window.remove(0);
+            }
+
+            if self.pos >= 64 && self.buzhash.is_trigger(self.modulus) {
+                self.bloom.add_feature(&self.window);
+            }
+
+            // Phase II: structural entropy over the raw byte stream. Emit a
+            // nibble each time a full block accumulates.
+            self.block.push(byte);
+            if self.block.len() == self.block_size {
+                let entropy = algo::calculate_entropy(&self.block);
+                self.nibbles.push(algo::entropy::quantize_entropy(entropy));
+                self.block.clear();
+            }
+
+            self.pos += 1;
+        }
+    }
+
+    /// Consume the builder and produce the final fingerprint
+    ///
+    /// Flushes the partial trailing structural block (if any) before packing the
+    /// nibbles, matching the `chunks` behavior of [`generate_structural_vector`].
+    pub fn finalize(mut self) -> FuzzyFingerprint {
+        if !self.block.is_empty() {
+            let entropy = algo::calculate_entropy(&self.block);
+            self.nibbles.push(algo::entropy::quantize_entropy(entropy));
+        }
+
+        let struct_data = algo::entropy::pack_nibbles(&self.nibbles);
+        FuzzyFingerprint::new(self.bloom.build(), struct_data)
+    }
+}
+
+impl std::io::Write for FingerprintBuilder {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `digest`-crate-compatible one-pass fuzzy hasher
+///
+/// Wraps the streaming [`LavinHasher`] behind the RustCrypto [`digest::Update`]
+/// trait, so LavinHash can sit in the same `Box<dyn Update>` pipelines as
+/// `Sha512` or `Blake2` and hash data that never fully fits in memory. Feed
+/// chunks with [`digest::Update::update`] (or the inherent
+/// [`update`](LavinDigest::update)) and call [`finalize`](LavinDigest::finalize)
+/// to emit the serialized fuzzy-hash digest.
+///
+/// Unlike a cryptographic hash the digest is variable length — its size tracks
+/// the input — so it is surfaced as a `Vec<u8>` (parse it back with
+/// [`FuzzyFingerprint::from_bytes`]) rather than through `FixedOutput`.
+pub struct LavinDigest {
+    inner: LavinHasher,
+}
+
+impl LavinDigest {
+    /// Create a streaming digest with the default configuration
+    ///
+    /// Without a size hint the trigger modulus falls back to the configured
+    /// minimum, so feature selection is stable however the stream is chunked.
+    pub fn new() -> Self {
+        Self {
+            inner: LavinHasher::new(&HashConfig::default(), None)
+                .expect("default config always has target_fpr == 0.0"),
+        }
+    }
+
+    /// Create a streaming digest with an explicit configuration and size hint
+    ///
+    /// Passing the eventual total as `size_hint` reproduces the one-shot feature
+    /// density of [`generate_hash`]; see [`LavinHasher::new`] for the details
+    /// (including the `target_fpr` error case).
+    pub fn with_config(config: &HashConfig, size_hint: Option<usize>) -> Result<Self, FingerprintError> {
+        Ok(Self {
+            inner: LavinHasher::new(config, size_hint)?,
+        })
+    }
+
+    /// Feed the next slice of input into the digest
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Consume the digest and emit the serialized fuzzy-hash bytes
+    pub fn finalize(self) -> Vec<u8> {
+        self.inner.finalize().to_bytes()
+    }
+
+    /// Consume the digest and return the structured [`FuzzyFingerprint`]
+    pub fn finalize_fingerprint(self) -> FuzzyFingerprint {
+        self.inner.finalize()
+    }
+}
+
+impl Default for LavinDigest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl digest::Update for LavinDigest {
+    fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+}
+
 // ============================================================================
 // FFI Layer - C-compatible exports
 // ============================================================================
@@ -262,6 +758,30 @@ pub extern "C" fn hf_config_set_min_modulus(cfg: *mut HashConfig, modulus: u64)
     }
 }
 
+/// Enable or disable the cross-entropy structural mode
+#[no_mangle]
+pub extern "C" fn hf_config_set_cross_entropy(cfg: *mut HashConfig, enable: bool) {
+    if !cfg.is_null() {
+        unsafe {
+            (*cfg).use_cross_entropy = enable;
+        }
+    }
+}
+
+/// Select the feature extraction mode (0 = CDC, 1 = longest-match repeat)
+#[no_mangle]
+pub extern "C" fn hf_config_set_feature_mode(cfg: *mut HashConfig, mode: u8) {
+    if !cfg.is_null() {
+        unsafe {
+            (*cfg).feature_mode = if mode == 1 {
+                FeatureMode::Repeat
+            } else {
+                FeatureMode::Cdc
+            };
+        }
+    }
+}
+
 /// Free configuration
 #[no_mangle]
 pub extern "C" fn hf_config_free(cfg: *mut HashConfig) {
@@ -382,6 +902,9 @@ impl Clone for HashConfig {
             enable_parallel: self.enable_parallel,
             alpha: self.alpha,
             min_modulus: self.min_modulus,
+            target_fpr: self.target_fpr,
+            use_cross_entropy: self.use_cross_entropy,
+            feature_mode: self.feature_mode,
         }
     }
 }
@@ -399,7 +922,7 @@ mod tests {
         assert!(result.is_ok());
 
         let fp = result.unwrap();
-        assert!(fp.size() > 0);
+        assert_eq!(fp.size(), fp.to_bytes().len());
     }
 
     #[test]
@@ -538,6 +1061,133 @@ mod tests {
         hf_config_free(cfg);
     }
 
+    #[test]
+    fn test_repeat_mode_detects_duplicated_block() {
+        // A base document, and a copy with a large block duplicated inside it.
+        let block = b"This is a repeated block of content that appears twice. ".repeat(8);
+        let filler = b"Some unique surrounding narrative text that differs a lot. ".repeat(8);
+
+        let mut original = Vec::new();
+        original.extend_from_slice(&filler);
+        original.extend_from_slice(&block);
+
+        let mut duplicated = Vec::new();
+        duplicated.extend_from_slice(&filler);
+        duplicated.extend_from_slice(&block);
+        duplicated.extend_from_slice(&block); // the block now appears twice
+
+        let mut config = HashConfig::default();
+        config.feature_mode = FeatureMode::Repeat;
+        config.enable_parallel = false;
+
+        let fp_orig = generate_hash(&original, &config).unwrap();
+        let fp_dup = generate_hash(&duplicated, &config).unwrap();
+
+        // The duplicated-block copy records extra repeat features but still
+        // shares most with the original.
+        let sim = compare_hashes(&fp_orig, &fp_dup, 0.0);
+        assert!(sim >= 40, "repeat-mode similarity too low: {}", sim);
+
+        // Repeat mode actually populates the content filter for repetitive data.
+        assert!(fp_dup.content_bloom().count_set_bits() > 0);
+    }
+
+    #[test]
+    fn test_lavinhasher_matches_generate_hash() {
+        let mut data = Vec::new();
+        for _ in 0..60 {
+            data.extend_from_slice(b"LavinHasher must match the one-shot generate_hash path. ");
+        }
+
+        let mut config = HashConfig::default();
+        config.enable_parallel = false;
+        let oneshot = generate_hash(&data, &config).unwrap();
+
+        // Streaming with an accurate size hint must reproduce it byte-for-byte.
+        let mut hasher = LavinHasher::new(&config, Some(data.len())).unwrap();
+        for chunk in data.chunks(11) {
+            hasher.update(chunk);
+        }
+        let streamed = hasher.finalize();
+
+        assert_eq!(oneshot.to_bytes(), streamed.to_bytes());
+    }
+
+    #[test]
+    fn test_lavinhasher_cross_entropy_flag() {
+        let data = b"cross-entropy streaming flag propagation check, needs some length here".repeat(4);
+        let mut config = HashConfig::default();
+        config.enable_parallel = false;
+        config.use_cross_entropy = true;
+
+        let mut hasher = LavinHasher::new(&config, Some(data.len())).unwrap();
+        hasher.update(&data);
+        let fp = hasher.finalize();
+
+        assert_eq!(fp.flags, model::FLAG_CROSS_ENTROPY);
+        assert_eq!(fp.to_bytes(), generate_hash(&data, &config).unwrap().to_bytes());
+    }
+
+    #[test]
+    fn test_lavin_digest_matches_lavinhasher() {
+        use digest::Update;
+
+        let data = b"digest-compatible streaming path, fed in through the Update trait. ".repeat(40);
+        let mut config = HashConfig::default();
+        config.enable_parallel = false;
+
+        // Driving the hasher through the digest `Update` trait must agree with
+        // the one-shot pipeline when the size hint is accurate.
+        let mut digest = LavinDigest::with_config(&config, Some(data.len())).unwrap();
+        for chunk in data.chunks(13) {
+            Update::update(&mut digest, chunk);
+        }
+        let streamed = digest.finalize();
+
+        assert_eq!(generate_hash(&data, &config).unwrap().to_bytes(), streamed);
+    }
+
+    #[test]
+    fn test_streaming_builder_matches_oneshot() {
+        use std::io::Write;
+
+        // Enough data to populate several structural blocks and bloom features.
+        let mut data = Vec::new();
+        for _ in 0..50 {
+            data.extend_from_slice(b"Streaming builder consistency check with enough content. ");
+        }
+
+        // One-shot with parallelism disabled so the modulus path matches the
+        // sequential builder (the hint equals the true length here).
+        let mut config = HashConfig::default();
+        config.enable_parallel = false;
+        let oneshot = generate_hash(&data, &config).unwrap();
+
+        // Stream the same bytes in awkward chunks through the Write impl.
+        let mut builder = FingerprintBuilder::new(data.len());
+        for chunk in data.chunks(7) {
+            builder.write_all(chunk).unwrap();
+        }
+        let streamed = builder.finalize();
+
+        assert_eq!(oneshot.to_bytes(), streamed.to_bytes());
+    }
+
+    #[test]
+    fn test_streaming_builder_chunk_invariance() {
+        let data: Vec<u8> = (0..5000).map(|i| (i % 97) as u8).collect();
+
+        let mut whole = FingerprintBuilder::new(data.len());
+        whole.update(&data);
+
+        let mut split = FingerprintBuilder::new(data.len());
+        for chunk in data.chunks(13) {
+            split.update(chunk);
+        }
+
+        assert_eq!(whole.finalize().to_bytes(), split.finalize().to_bytes());
+    }
+
     #[test]
     fn test_parallel_vs_sequential() {
         // Create large enough data to trigger parallel processing
@@ -552,8 +1202,10 @@ mod tests {
         let fp_seq = generate_hash(&data, &config_seq).unwrap();
         let fp_par = generate_hash(&data, &config_par).unwrap();
 
-        // Results should be similar (might not be identical due to chunking)
+        // Overlapping chunk windows make the parallel path bit-for-bit identical
+        // to the sequential one.
+        assert_eq!(fp_seq.to_bytes(), fp_par.to_bytes());
         let similarity = compare_hashes(&fp_seq, &fp_par, DEFAULT_ALPHA);
-        assert!(similarity > 80, "Parallel and sequential should produce similar results");
+        assert_eq!(similarity, 100, "Parallel and sequential must be identical");
     }
 }
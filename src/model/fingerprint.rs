@@ -11,24 +11,42 @@ const MAGIC_BYTE: u8 = 0x48;
 /// Current version of the fingerprint format
 const VERSION: u8 = 0x01;
 
-/// Minimum size for a valid fingerprint
-const MIN_FINGERPRINT_SIZE: usize = 4 + BLOOM_SIZE_BYTES; // Header + Bloom
+/// Minimum size for a valid fingerprint: the 4-byte header
+const MIN_FINGERPRINT_SIZE: usize = 4;
+
+/// `flags` bit 0: structural vector holds cross-entropy nibbles (vs Shannon)
+pub const FLAG_CROSS_ENTROPY: u8 = 0x01;
+
+/// TLV section tag: content Bloom filter words
+const TAG_CONTENT_BLOOM: u8 = 0x01;
+
+/// TLV section tag: packed structural nibbles
+const TAG_STRUCT_NIBBLES: u8 = 0x02;
+
+/// TLV section tag: `BloomHasher` policy name (UTF-8)
+const TAG_HASHER_NAME: u8 = 0x03;
 
 /// FuzzyFingerprint - The core fingerprint structure
 ///
-/// Binary format:
+/// Binary format (TLV, forward-compatible):
 /// - Offset 0x00: Magic (0x48 = 'H')
 /// - Offset 0x01: Version (0x01)
-/// - Offset 0x02-0x03: Struct Length (u16 LE)
-/// - Offset 0x04-0x403: Content Bloom Filter (1024 bytes)
-/// - Offset 0x404+: Structure Data (variable length)
+/// - Offset 0x02: Mode flags (see [`FLAG_CROSS_ENTROPY`])
+/// - Offset 0x03: Reserved (0x00)
+/// - Offset 0x04+: a sequence of `(tag: u8, len: u32 LE, payload)` sections.
+///
+/// Known tags are `0x01` (content Bloom words), `0x02` (structural nibbles) and
+/// `0x03` (hasher policy name). Unknown tags are skipped rather than fatal, so
+/// future versions can add sections without breaking older readers, and trailing
+/// sections a reader does not understand are ignored.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq)]
 pub struct FuzzyFingerprint {
     /// Version of the fingerprint format
     pub version: u8,
 
-    /// Flags for future use (currently unused)
+    /// Mode flags. Bit 0 ([`FLAG_CROSS_ENTROPY`]) selects the cross-entropy
+    /// structural descriptor; remaining bits are reserved.
     pub flags: u8,
 
     /// Length of the structural data in bytes
@@ -39,18 +57,43 @@ pub struct FuzzyFingerprint {
 
     /// Structural data (entropy nibbles packed)
     pub struct_data: Vec<u8>,
+
+    /// Name of the `BloomHasher` policy the content filter was built with
+    ///
+    /// Fingerprints built with different hashing policies address different bit
+    /// spaces, so [`similarity`](FuzzyFingerprint::similarity) refuses to
+    /// compare them. Carried across `to_bytes`/`from_bytes` in the `0x03`
+    /// section; buffers that omit it deserialize as the default `"fx"` policy.
+    pub hasher_name: String,
 }
 
 impl FuzzyFingerprint {
     /// Create a new fingerprint
     pub fn new(content_bloom: BloomFilter, structural_data: Vec<u8>) -> Self {
+        Self::new_with_flags(content_bloom, structural_data, 0)
+    }
+
+    /// Create a new fingerprint with explicit mode flags
+    ///
+    /// Use [`FLAG_CROSS_ENTROPY`] when `structural_data` was built with
+    /// [`crate::algo::generate_structural_vector_cross`]; comparisons between
+    /// fingerprints with mismatched mode flags are rejected.
+    pub fn new_with_flags(content_bloom: BloomFilter, structural_data: Vec<u8>, flags: u8) -> Self {
         let struct_len = structural_data.len() as u16;
+        let hasher_name = content_bloom.hasher_name().to_string();
 
+        // Project the bloom into the fixed 128-word content slot. Auto-sized
+        // filters (see `HashConfig::target_fpr`) larger or smaller than the
+        // default are copied word-for-word as far as they fit; the serialized
+        // content section always carries exactly 8192 bits.
         let bloom_bytes = content_bloom.to_bytes();
         let mut content_hash = [0u64; 128];
-        for i in 0..128 {
+        for (i, slot) in content_hash.iter_mut().enumerate() {
             let offset = i * 8;
-            content_hash[i] = u64::from_le_bytes([
+            if offset + 8 > bloom_bytes.len() {
+                break;
+            }
+            *slot = u64::from_le_bytes([
                 bloom_bytes[offset],
                 bloom_bytes[offset + 1],
                 bloom_bytes[offset + 2],
@@ -64,35 +107,47 @@ impl FuzzyFingerprint {
 
         Self {
             version: VERSION,
-            flags: 0,
+            flags,
             struct_len,
             content_hash,
             struct_data: structural_data,
+            hasher_name,
         }
     }
 
     /// Serialize the fingerprint to bytes
     pub fn to_bytes(&self) -> Vec<u8> {
-        let total_size = 4 + BLOOM_SIZE_BYTES + self.struct_data.len();
+        let total_size = 4 + (5 + BLOOM_SIZE_BYTES) + (5 + self.struct_data.len())
+            + (5 + self.hasher_name.len());
         let mut bytes = Vec::with_capacity(total_size);
 
-        // Header
+        // Header: magic, version, flags, reserved
         bytes.push(MAGIC_BYTE);
         bytes.push(self.version);
-        bytes.extend_from_slice(&self.struct_len.to_le_bytes());
+        bytes.push(self.flags);
+        bytes.push(0x00);
 
-        // Content Bloom Filter (16KB)
+        // Section 0x01: content Bloom filter words
+        let mut bloom_payload = Vec::with_capacity(BLOOM_SIZE_BYTES);
         for &word in &self.content_hash {
-            bytes.extend_from_slice(&word.to_le_bytes());
+            bloom_payload.extend_from_slice(&word.to_le_bytes());
         }
+        push_section(&mut bytes, TAG_CONTENT_BLOOM, &bloom_payload);
+
+        // Section 0x02: packed structural nibbles
+        push_section(&mut bytes, TAG_STRUCT_NIBBLES, &self.struct_data);
 
-        // Structural data
-        bytes.extend_from_slice(&self.struct_data);
+        // Section 0x03: hasher policy name
+        push_section(&mut bytes, TAG_HASHER_NAME, self.hasher_name.as_bytes());
 
         bytes
     }
 
     /// Deserialize fingerprint from bytes
+    ///
+    /// Walks the TLV sections, filling the known ones and skipping any tag it
+    /// does not recognize, so buffers written by a future version round-trip
+    /// their shared sections without error.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, FingerprintError> {
         if bytes.len() < MIN_FINGERPRINT_SIZE {
             return Err(FingerprintError::InvalidSize);
@@ -108,43 +163,114 @@ impl FuzzyFingerprint {
             return Err(FingerprintError::UnsupportedVersion(version));
         }
 
-        let struct_len = u16::from_le_bytes([bytes[2], bytes[3]]);
+        let flags = bytes[2];
 
-        // Extract content hash
         let mut content_hash = [0u64; 128];
-        for i in 0..128 {
-            let offset = 4 + i * 8;
-            content_hash[i] = u64::from_le_bytes([
-                bytes[offset],
+        let mut struct_data: Vec<u8> = Vec::new();
+        // Buffers that omit the hasher section assume the default policy.
+        let mut hasher_name = "fx".to_string();
+
+        let mut offset = 4;
+        while offset < bytes.len() {
+            // A section header is tag (1) + length (4). A short tail is treated
+            // as end-of-stream rather than corruption.
+            if offset + 5 > bytes.len() {
+                break;
+            }
+            let tag = bytes[offset];
+            let len = u32::from_le_bytes([
                 bytes[offset + 1],
                 bytes[offset + 2],
                 bytes[offset + 3],
                 bytes[offset + 4],
-                bytes[offset + 5],
-                bytes[offset + 6],
-                bytes[offset + 7],
-            ]);
-        }
-
-        // Extract structural data
-        let struct_data_offset = 4 + BLOOM_SIZE_BYTES;
-        let expected_end = struct_data_offset + struct_len as usize;
-
-        if bytes.len() < expected_end {
-            return Err(FingerprintError::InvalidSize);
+            ]) as usize;
+            let payload_start = offset + 5;
+            let payload_end = payload_start + len;
+            if payload_end > bytes.len() {
+                return Err(FingerprintError::InvalidSize);
+            }
+            let payload = &bytes[payload_start..payload_end];
+
+            match tag {
+                TAG_CONTENT_BLOOM => {
+                    // Copy as many whole words as fit the fixed 128-word slot.
+                    for (i, slot) in content_hash.iter_mut().enumerate() {
+                        let o = i * 8;
+                        if o + 8 > payload.len() {
+                            break;
+                        }
+                        *slot = u64::from_le_bytes([
+                            payload[o],
+                            payload[o + 1],
+                            payload[o + 2],
+                            payload[o + 3],
+                            payload[o + 4],
+                            payload[o + 5],
+                            payload[o + 6],
+                            payload[o + 7],
+                        ]);
+                    }
+                }
+                TAG_STRUCT_NIBBLES => struct_data = payload.to_vec(),
+                TAG_HASHER_NAME => {
+                    hasher_name = String::from_utf8_lossy(payload).into_owned()
+                }
+                // Unknown tag from a future version: skip its payload.
+                _ => {}
+            }
+
+            offset = payload_end;
         }
 
-        let struct_data = bytes[struct_data_offset..expected_end].to_vec();
-
         Ok(Self {
             version,
-            flags: 0,
-            struct_len,
+            flags,
+            struct_len: struct_data.len() as u16,
             content_hash,
             struct_data,
+            hasher_name,
         })
     }
 
+    /// Serialize the fingerprint to a lowercase hex string
+    pub fn to_hex(&self) -> String {
+        let bytes = self.to_bytes();
+        let mut s = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            s.push(HEX_CHARS[(byte >> 4) as usize] as char);
+            s.push(HEX_CHARS[(byte & 0x0F) as usize] as char);
+        }
+        s
+    }
+
+    /// Parse a fingerprint from a hex string, validating length and charset
+    pub fn from_hex(s: &str) -> Result<Self, FingerprintError> {
+        let s = s.as_bytes();
+        if s.len() % 2 != 0 {
+            return Err(FingerprintError::InvalidEncoding);
+        }
+
+        let mut bytes = Vec::with_capacity(s.len() / 2);
+        for pair in s.chunks_exact(2) {
+            let hi = hex_val(pair[0]).ok_or(FingerprintError::InvalidEncoding)?;
+            let lo = hex_val(pair[1]).ok_or(FingerprintError::InvalidEncoding)?;
+            bytes.push((hi << 4) | lo);
+        }
+
+        Self::from_bytes(&bytes)
+    }
+
+    /// Serialize the fingerprint to a standard base64 string (with padding)
+    pub fn to_base64(&self) -> String {
+        base64_encode(&self.to_bytes())
+    }
+
+    /// Parse a fingerprint from a base64 string, validating charset and padding
+    pub fn from_base64(s: &str) -> Result<Self, FingerprintError> {
+        let bytes = base64_decode(s)?;
+        Self::from_bytes(&bytes)
+    }
+
     /// Get the content Bloom filter
     pub fn content_bloom(&self) -> BloomFilter {
         let mut bytes = [0u8; BLOOM_SIZE_BYTES];
@@ -166,6 +292,12 @@ impl FuzzyFingerprint {
     ///
     /// Returns similarity score 0-100
     pub fn similarity(&self, other: &FuzzyFingerprint, alpha: f32) -> u8 {
+        // Refuse to compare fingerprints built with different hashing policies
+        // or structural modes: their bits/nibbles address unrelated spaces.
+        if self.hasher_name != other.hasher_name || self.flags != other.flags {
+            return 0;
+        }
+
         // Content similarity (Jaccard on Bloom filters)
         let content_sim = self.content_bloom().jaccard_similarity(&other.content_bloom());
 
@@ -183,9 +315,29 @@ impl FuzzyFingerprint {
         (combined * 100.0).floor().min(100.0).max(0.0) as u8
     }
 
-    /// Get fingerprint size in bytes
+    /// Calculate similarity using the cardinality-corrected content estimator
+    ///
+    /// Identical to [`similarity`](FuzzyFingerprint::similarity) except the
+    /// content term uses [`BloomFilter::estimated_jaccard`], which corrects for
+    /// bloom saturation and gives a more accurate score for near-full filters.
+    pub fn estimated_similarity(&self, other: &FuzzyFingerprint, alpha: f32) -> u8 {
+        if self.hasher_name != other.hasher_name || self.flags != other.flags {
+            return 0;
+        }
+
+        let content_sim = self
+            .content_bloom()
+            .estimated_jaccard(&other.content_bloom()) as f32;
+
+        let struct_sim = crate::algo::structural_similarity(&self.struct_data, &other.struct_data);
+
+        let combined = alpha * struct_sim + (1.0 - alpha) * content_sim;
+        (combined * 100.0).floor().clamp(0.0, 100.0) as u8
+    }
+
+    /// Get the serialized fingerprint size in bytes
     pub fn size(&self) -> usize {
-        4 + BLOOM_SIZE_BYTES + self.struct_data.len()
+        self.to_bytes().len()
     }
 }
 
@@ -201,6 +353,103 @@ impl fmt::Display for FuzzyFingerprint {
     }
 }
 
+/// Append a `(tag, len: u32 LE, payload)` TLV section to `out`
+#[inline]
+fn push_section(out: &mut Vec<u8>, tag: u8, payload: &[u8]) {
+    out.push(tag);
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(payload);
+}
+
+/// Lowercase hex digit table
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+/// Standard base64 alphabet
+const B64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Decode a single hex digit, accepting upper- and lowercase
+#[inline]
+fn hex_val(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Encode bytes as standard base64 with `=` padding
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = *chunk.get(1).unwrap_or(&0) as u32;
+        let b2 = *chunk.get(2).unwrap_or(&0) as u32;
+        let triple = (b0 << 16) | (b1 << 8) | b2;
+
+        out.push(B64_CHARS[((triple >> 18) & 0x3F) as usize] as char);
+        out.push(B64_CHARS[((triple >> 12) & 0x3F) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            B64_CHARS[((triple >> 6) & 0x3F) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            B64_CHARS[(triple & 0x3F) as usize] as char
+        } else {
+            '='
+        });
+    }
+
+    out
+}
+
+/// Decode a standard base64 string, validating charset and padding
+fn base64_decode(s: &str) -> Result<Vec<u8>, FingerprintError> {
+    let s = s.as_bytes();
+    if s.is_empty() || s.len() % 4 != 0 {
+        return Err(FingerprintError::InvalidEncoding);
+    }
+
+    let decode_char = |c: u8| -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    };
+
+    let num_chunks = s.len() / 4;
+    let mut out = Vec::with_capacity(num_chunks * 3);
+    for (ci, chunk) in s.chunks_exact(4).enumerate() {
+        let pad = chunk.iter().filter(|&&c| c == b'=').count();
+        // Padding may only appear in the final chunk, in the last 1-2 positions.
+        if pad > 0 && (ci != num_chunks - 1 || chunk[0] == b'=' || chunk[1] == b'=') {
+            return Err(FingerprintError::InvalidEncoding);
+        }
+
+        let mut acc = 0u32;
+        for &c in chunk {
+            let v = if c == b'=' { 0 } else { decode_char(c).ok_or(FingerprintError::InvalidEncoding)? };
+            acc = (acc << 6) | v;
+        }
+
+        out.push((acc >> 16) as u8);
+        if pad < 2 {
+            out.push((acc >> 8) as u8);
+        }
+        if pad < 1 {
+            out.push(acc as u8);
+        }
+    }
+
+    Ok(out)
+}
+
 /// Errors that can occur during fingerprint operations
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FingerprintError {
@@ -212,6 +461,16 @@ pub enum FingerprintError {
 
     /// Unsupported version
     UnsupportedVersion(u8),
+
+    /// Malformed hex/base64 encoding (bad length or charset)
+    InvalidEncoding,
+
+    /// Configuration the serialized format cannot represent yet
+    ///
+    /// The content section is still a fixed 8192-bit slot, so an auto-sized
+    /// filter (`target_fpr > 0`) cannot round-trip its `m`/`k`. Rejected until
+    /// the variable-width content section exists.
+    UnsupportedConfig,
 }
 
 impl fmt::Display for FingerprintError {
@@ -220,6 +479,8 @@ impl fmt::Display for FingerprintError {
             Self::InvalidSize => write!(f, "Invalid fingerprint size"),
             Self::InvalidMagic => write!(f, "Invalid magic byte"),
             Self::UnsupportedVersion(v) => write!(f, "Unsupported version: {}", v),
+            Self::InvalidEncoding => write!(f, "Malformed fingerprint encoding"),
+            Self::UnsupportedConfig => write!(f, "Unsupported hash configuration"),
         }
     }
 }
@@ -256,12 +517,42 @@ mod tests {
 
         let bytes = fp.to_bytes();
 
-        // Check magic and version
+        // Check header: magic, version, flags, reserved
         assert_eq!(bytes[0], MAGIC_BYTE);
         assert_eq!(bytes[1], VERSION);
+        assert_eq!(bytes[2], 0); // no mode flags
+        assert_eq!(bytes[3], 0); // reserved
 
-        // Check total size
-        assert_eq!(bytes.len(), 4 + BLOOM_SIZE_BYTES + 2);
+        // First section is the content bloom (tag 0x01, 1024-byte payload)
+        assert_eq!(bytes[4], TAG_CONTENT_BLOOM);
+        let bloom_len = u32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]) as usize;
+        assert_eq!(bloom_len, BLOOM_SIZE_BYTES);
+    }
+
+    #[test]
+    fn test_fingerprint_skips_unknown_section() {
+        let mut bloom = BloomFilter::new();
+        bloom.insert(b"forward compat");
+        let fp = FuzzyFingerprint::new(bloom, vec![0x01, 0x02]);
+
+        // Append a well-formed section with a tag no current reader knows.
+        let mut bytes = fp.to_bytes();
+        push_section(&mut bytes, 0x7F, b"future payload");
+
+        let restored = FuzzyFingerprint::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.struct_data, fp.struct_data);
+        assert_eq!(restored.content_hash, fp.content_hash);
+    }
+
+    #[test]
+    fn test_fingerprint_flags_roundtrip() {
+        let mut bloom = BloomFilter::new();
+        bloom.insert(b"flagged");
+        let fp = FuzzyFingerprint::new_with_flags(bloom, vec![0x09], FLAG_CROSS_ENTROPY);
+
+        let restored = FuzzyFingerprint::from_bytes(&fp.to_bytes()).unwrap();
+        assert_eq!(restored.flags, FLAG_CROSS_ENTROPY);
+        assert_eq!(restored.hasher_name, fp.hasher_name);
     }
 
     #[test]
@@ -281,6 +572,43 @@ mod tests {
         assert_eq!(fp1.content_hash, fp2.content_hash);
     }
 
+    #[test]
+    fn test_fingerprint_hex_roundtrip() {
+        let mut bloom = BloomFilter::new();
+        bloom.insert(b"hex feature");
+        let fp = FuzzyFingerprint::new(bloom, vec![0xDE, 0xAD, 0xBE]);
+
+        let hex = fp.to_hex();
+        assert!(hex.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(hex.len(), fp.to_bytes().len() * 2);
+
+        let restored = FuzzyFingerprint::from_hex(&hex).unwrap();
+        assert_eq!(fp.to_bytes(), restored.to_bytes());
+    }
+
+    #[test]
+    fn test_fingerprint_base64_roundtrip() {
+        let mut bloom = BloomFilter::new();
+        bloom.insert(b"b64 feature");
+        let fp = FuzzyFingerprint::new(bloom, vec![0x01, 0x02, 0x03, 0x04, 0x05]);
+
+        let b64 = fp.to_base64();
+        let restored = FuzzyFingerprint::from_base64(&b64).unwrap();
+        assert_eq!(fp.to_bytes(), restored.to_bytes());
+    }
+
+    #[test]
+    fn test_fingerprint_from_hex_malformed() {
+        assert_eq!(FuzzyFingerprint::from_hex("abc"), Err(FingerprintError::InvalidEncoding));
+        assert_eq!(FuzzyFingerprint::from_hex("zz"), Err(FingerprintError::InvalidEncoding));
+    }
+
+    #[test]
+    fn test_fingerprint_from_base64_malformed() {
+        assert_eq!(FuzzyFingerprint::from_base64("AB"), Err(FingerprintError::InvalidEncoding));
+        assert_eq!(FuzzyFingerprint::from_base64("!!!!"), Err(FingerprintError::InvalidEncoding));
+    }
+
     #[test]
     fn test_fingerprint_invalid_magic() {
         let mut bytes = vec![0xFF, VERSION]; // Invalid magic
@@ -2,4 +2,4 @@
 
 pub mod fingerprint;
 
-pub use fingerprint::{FuzzyFingerprint, FingerprintError};
+pub use fingerprint::{FuzzyFingerprint, FingerprintError, FLAG_CROSS_ENTROPY};
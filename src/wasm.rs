@@ -55,6 +55,37 @@ pub fn wasm_compare_hashes(hash_a: &[u8], hash_b: &[u8]) -> Result<u8, JsValue>
     Ok(compare_hashes(&fp_a, &fp_b, 0.3))
 }
 
+/// Generate a fuzzy hash and return it as a hex string (WASM wrapper)
+///
+/// Unlike [`wasm_generate_hash`], the result is a copy-pasteable printable
+/// string rather than a raw `Uint8Array` that JS must re-encode.
+#[wasm_bindgen]
+pub fn wasm_generate_hash_hex(data: &[u8]) -> Result<String, JsValue> {
+    let config = HashConfig::default();
+
+    let fingerprint = generate_hash(data, &config)
+        .map_err(|e| JsValue::from_str(&format!("Error generating hash: {:?}", e)))?;
+
+    Ok(fingerprint.to_hex())
+}
+
+/// Compare two hex-encoded fuzzy hashes (WASM wrapper)
+///
+/// # Returns
+/// Similarity score 0-100
+#[wasm_bindgen]
+pub fn wasm_compare_hashes_hex(hash_a: &str, hash_b: &str) -> Result<u8, JsValue> {
+    use crate::model::FuzzyFingerprint;
+
+    let fp_a = FuzzyFingerprint::from_hex(hash_a)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing hash A: {:?}", e)))?;
+
+    let fp_b = FuzzyFingerprint::from_hex(hash_b)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing hash B: {:?}", e)))?;
+
+    Ok(compare_hashes(&fp_a, &fp_b, 0.3))
+}
+
 /// Generate hash and compare in one step (WASM wrapper)
 ///
 /// # Arguments